@@ -0,0 +1,215 @@
+use std::{fmt::Display, fs, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::solution::{Day, Part};
+
+/// Summary of a set of timing samples.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "need at least one sample");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+        let seconds: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = seconds.iter().sum::<f64>() / seconds.len() as f64;
+        let variance =
+            seconds.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / seconds.len() as f64;
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:.2?} median {:.2?} mean {:.2?} ± {:.2?}",
+            self.min, self.median, self.mean, self.stddev
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub day: usize,
+    pub part: u8,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+impl Measurement {
+    pub fn total_median(&self) -> Duration {
+        self.parse.median + self.solve.median
+    }
+}
+
+/// Times `part` of `day` `iterations` times after `warmup` untimed runs. The
+/// input is read up front so disk access is not counted as parsing.
+pub fn measure(
+    day: usize,
+    solution: &dyn Day,
+    path: &Path,
+    part: Part,
+    warmup: usize,
+    iterations: usize,
+) -> Result<Measurement> {
+    let input = fs::read_to_string(path)?;
+    for _ in 0..warmup {
+        solution.time(&input, part)?;
+    }
+    let mut parse = Vec::with_capacity(iterations);
+    let mut solve = Vec::with_capacity(iterations);
+    for _ in 0..iterations.max(1) {
+        let (parse_time, solve_time) = solution.time(&input, part)?;
+        parse.push(parse_time);
+        solve.push(solve_time);
+    }
+    Ok(Measurement {
+        day,
+        part: part.into(),
+        parse: Stats::from_samples(&parse),
+        solve: Stats::from_samples(&solve),
+    })
+}
+
+pub fn save_baseline(path: &Path, measurements: &[Measurement]) -> Result<()> {
+    let json = serde_json::to_string_pretty(measurements)
+        .map_err(|err| Error::semantic(format!("could not encode baseline: {}", err)))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+pub fn load_baseline(path: &Path) -> Result<Vec<Measurement>> {
+    serde_json::from_str(&fs::read_to_string(path)?).map_err(|err| {
+        Error::semantic(format!(
+            "could not read baseline {}: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+/// A day and part whose median got slower than the baseline allows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: usize,
+    pub part: u8,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Regression {
+    pub fn slowdown(&self) -> f64 {
+        self.current.as_secs_f64() / self.baseline.as_secs_f64()
+    }
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {} part {} regressed: {:.2?} -> {:.2?} ({:+.1}%)",
+            self.day,
+            self.part,
+            self.baseline,
+            self.current,
+            (self.slowdown() - 1.0) * 100.0
+        )
+    }
+}
+
+/// Flags every measurement whose total median exceeds its baseline by more
+/// than `threshold` (0.1 allows 10% noise). Parts missing from the baseline
+/// are skipped.
+pub fn regressions(
+    baseline: &[Measurement],
+    current: &[Measurement],
+    threshold: f64,
+) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|measurement| {
+            let previous = baseline
+                .iter()
+                .find(|b| b.day == measurement.day && b.part == measurement.part)?;
+            let allowed = previous.total_median().as_secs_f64() * (1.0 + threshold);
+            if measurement.total_median().as_secs_f64() > allowed {
+                Some(Regression {
+                    day: measurement.day,
+                    part: measurement.part,
+                    baseline: previous.total_median(),
+                    current: measurement.total_median(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(median_ms: u64) -> Stats {
+        let median = Duration::from_millis(median_ms);
+        Stats {
+            min: median,
+            median,
+            mean: median,
+            stddev: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = [4, 1, 3, 2]
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(Duration::from_millis(1), stats.min);
+        assert_eq!(Duration::from_micros(2500), stats.median);
+        assert_eq!(Duration::from_micros(2500), stats.mean);
+        assert_eq!(1118, stats.stddev.as_micros());
+    }
+
+    #[test]
+    fn test_regressions() {
+        let measurement = |day, parse, solve| Measurement {
+            day,
+            part: 1,
+            parse: stats(parse),
+            solve: stats(solve),
+        };
+        let baseline = vec![measurement(1, 1, 10), measurement(2, 1, 10)];
+        let current = vec![
+            measurement(1, 1, 11),
+            measurement(2, 1, 20),
+            measurement(3, 1, 100),
+        ];
+        let found = regressions(&baseline, &current, 0.1);
+        assert_eq!(1, found.len());
+        assert_eq!(2, found[0].day);
+        assert_eq!(Duration::from_millis(11), found[0].baseline);
+    }
+}
@@ -1,10 +1,11 @@
-use std::{cmp, path::PathBuf};
+use std::{cmp, io::BufRead};
 
 use array2d::Array2D;
 
-use crate::utils::read_lines;
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
 
-fn build_state(input: &Vec<Vec<usize>>) -> Array2D<bool> {
+fn build_state(input: &[Vec<usize>]) -> Array2D<bool> {
     let rows = input.len();
     let cols = input
         .iter()
@@ -14,7 +15,7 @@ fn build_state(input: &Vec<Vec<usize>>) -> Array2D<bool> {
     Array2D::filled_with(false, rows, cols)
 }
 
-fn build_input(input: &Vec<Vec<usize>>) -> Array2D<usize> {
+fn build_input(input: &[Vec<usize>]) -> Array2D<usize> {
     Array2D::from_rows(input).expect("Expected square input")
 }
 
@@ -23,9 +24,7 @@ fn visibility(input: &Array2D<usize>, x: usize, y: usize) -> usize {
     let right = visibility_right(input, x, y);
     let up = visibility_up(input, x, y);
     let down = visibility_down(input, x, y);
-    let total = left * right * up * down;
-
-    total
+    left * right * up * down
 }
 
 fn visibility_down(input: &Array2D<usize>, x: usize, y: usize) -> usize {
@@ -47,7 +46,7 @@ fn visibility_down(input: &Array2D<usize>, x: usize, y: usize) -> usize {
 
 fn visibility_up(input: &Array2D<usize>, x: usize, y: usize) -> usize {
     let max_val = input.get(y, x).expect("Invalid X & Y");
-    if y <= 0 {
+    if y == 0 {
         0
     } else {
         let mut count = 0;
@@ -85,7 +84,7 @@ fn visibility_right(input: &Array2D<usize>, x: usize, y: usize) -> usize {
 
 fn visibility_left(input: &Array2D<usize>, x: usize, y: usize) -> usize {
     let max_val = input.get(y, x).expect("Invalid X & Y");
-    if x <= 0 {
+    if x == 0 {
         0
     } else {
         let mut count = 0;
@@ -104,85 +103,7 @@ fn visibility_left(input: &Array2D<usize>, x: usize, y: usize) -> usize {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    pub fn test_visibility_up() {
-        let input: Array2D<usize> = Array2D::from_rows(&vec![
-            vec![3, 0, 3, 7, 3],
-            vec![2, 5, 5, 1, 2],
-            vec![6, 5, 3, 3, 2],
-            vec![3, 3, 5, 4, 9],
-            vec![3, 5, 3, 9, 0],
-        ])
-        .unwrap();
-        assert_eq!(1, visibility_up(&input, 3, 1));
-        assert_eq!(0, visibility_up(&input, 1, 0));
-        assert_eq!(1, visibility_up(&input, 2, 4));
-        assert_eq!(4, visibility_up(&input, 3, 4));
-        assert_eq!(3, visibility_up(&input, 3, 3));
-        assert_eq!(2, visibility_up(&input, 2, 3));
-    }
-
-    #[test]
-    pub fn test_visibility_down() {
-        let input: Array2D<usize> = Array2D::from_rows(&vec![
-            vec![3, 0, 3, 7, 3],
-            vec![2, 5, 5, 1, 2],
-            vec![6, 5, 3, 3, 2],
-            vec![3, 3, 5, 4, 9],
-            vec![3, 5, 3, 9, 0],
-        ])
-        .unwrap();
-        assert_eq!(0, visibility_down(&input, 0, 4));
-        assert_eq!(0, visibility_down(&input, 4, 4));
-        assert_eq!(2, visibility_down(&input, 0, 0));
-        assert_eq!(1, visibility_down(&input, 1, 0));
-        assert_eq!(3, visibility_down(&input, 4, 0));
-        assert_eq!(1, visibility_down(&input, 1, 1));
-        assert_eq!(1, visibility_down(&input, 2, 3));
-    }
-
-    #[test]
-    pub fn test_visibility_left() {
-        let input: Array2D<usize> = Array2D::from_rows(&vec![
-            vec![3, 0, 3, 7, 3],
-            vec![2, 5, 5, 1, 2],
-            vec![6, 5, 3, 3, 2],
-            vec![3, 3, 5, 4, 9],
-            vec![3, 5, 3, 9, 0],
-        ])
-        .unwrap();
-        assert_eq!(0, visibility_left(&input, 0, 0));
-        assert_eq!(1, visibility_left(&input, 1, 0));
-        assert_eq!(1, visibility_left(&input, 1, 1));
-        assert_eq!(1, visibility_left(&input, 3, 2));
-        assert_eq!(4, visibility_left(&input, 4, 3));
-        assert_eq!(2, visibility_left(&input, 2, 3));
-    }
-
-    #[test]
-    pub fn test_visibility_right() {
-        let input: Array2D<usize> = Array2D::from_rows(&vec![
-            vec![3, 0, 3, 7, 3],
-            vec![2, 5, 5, 1, 2],
-            vec![6, 5, 3, 3, 2],
-            vec![3, 3, 5, 4, 9],
-            vec![3, 5, 3, 9, 0],
-        ])
-        .unwrap();
-        assert_eq!(0, visibility_right(&input, 4, 0));
-        assert_eq!(1, visibility_right(&input, 3, 1));
-        assert_eq!(1, visibility_right(&input, 3, 2));
-        assert_eq!(1, visibility_right(&input, 2, 2));
-        assert_eq!(1, visibility_right(&input, 2, 2));
-        assert_eq!(2, visibility_right(&input, 2, 3));
-    }
-}
-
-fn max_visibility(input: &Vec<Vec<usize>>) -> usize {
+fn max_visibility(input: &[Vec<usize>]) -> usize {
     let input_array = Array2D::from_rows(input).expect("Could not build 2D Array()");
 
     input_array
@@ -197,6 +118,8 @@ fn iterate(state: &mut Array2D<bool>, input: &Array2D<usize>) {
     iterate_reverse(state, input);
 }
 
+// Edge trees and trees taller than the rest so far are separate cases.
+#[allow(clippy::if_same_then_else)]
 fn iterate_normal(state: &mut Array2D<bool>, input: &Array2D<usize>) {
     let cols = input.column_len();
     let rows = input.row_len();
@@ -224,6 +147,8 @@ fn iterate_normal(state: &mut Array2D<bool>, input: &Array2D<usize>) {
     }
 }
 
+// Edge trees and trees taller than the rest so far are separate cases.
+#[allow(clippy::if_same_then_else)]
 fn iterate_reverse(state: &mut Array2D<bool>, input: &Array2D<usize>) {
     let cols = input.column_len();
     let rows = input.row_len();
@@ -261,27 +186,109 @@ fn count(state: Array2D<bool>) -> usize {
         .count()
 }
 
-pub fn run(path: &PathBuf, bonus: bool) -> usize {
-    if let Ok(lines) = read_lines(path) {
+pub struct DayEight;
+
+impl Solution for DayEight {
+    type Input = Vec<Vec<usize>>;
+    type Answer = usize;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
         let mut input_vec: Vec<Vec<usize>> = Vec::new();
-        for maybe_line in lines {
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
             let mut current_line: Vec<usize> = Vec::new();
-            if let Ok(line) = maybe_line {
-                for character in line.chars() {
-                    current_line
-                        .push(character.to_digit(10).expect("Expected valid ASCII digit") as usize);
+            for character in line.chars() {
+                let digit = character.to_digit(10).ok_or_else(|| {
+                    Error::parse(index + 1, &line, format!("'{}' is not a digit", character))
+                })?;
+                current_line.push(digit as usize);
+            }
+            if let Some(first) = input_vec.first() {
+                if first.len() != current_line.len() {
+                    return Err(Error::parse(
+                        index + 1,
+                        &line,
+                        format!("expected {} trees per row", first.len()),
+                    ));
                 }
-                input_vec.push(current_line);
             }
+            input_vec.push(current_line);
         }
-        if bonus {
-            return max_visibility(&input_vec);
-        } else {
-            let input = build_input(&input_vec);
-            let mut state = build_state(&input_vec);
-            iterate(&mut state, &input);
-            return count(state);
+        if input_vec.is_empty() || input_vec[0].is_empty() {
+            return Err(Error::semantic("expected a non-empty grid of trees"));
         }
+        Ok(input_vec)
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        let array = build_input(input);
+        let mut state = build_state(input);
+        iterate(&mut state, &array);
+        Ok(count(state))
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(max_visibility(input))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../fixtures/8.txt"),
+            part_one: Some("21"),
+            part_two: Some("8"),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{example_input, test_examples};
+
+    test_examples!(DayEight);
+
+    #[test]
+    pub fn test_visibility_up() {
+        let input = build_input(&example_input!(DayEight));
+        assert_eq!(1, visibility_up(&input, 3, 1));
+        assert_eq!(0, visibility_up(&input, 1, 0));
+        assert_eq!(1, visibility_up(&input, 2, 4));
+        assert_eq!(4, visibility_up(&input, 3, 4));
+        assert_eq!(3, visibility_up(&input, 3, 3));
+        assert_eq!(2, visibility_up(&input, 2, 3));
+    }
+
+    #[test]
+    pub fn test_visibility_down() {
+        let input = build_input(&example_input!(DayEight));
+        assert_eq!(0, visibility_down(&input, 0, 4));
+        assert_eq!(0, visibility_down(&input, 4, 4));
+        assert_eq!(2, visibility_down(&input, 0, 0));
+        assert_eq!(1, visibility_down(&input, 1, 0));
+        assert_eq!(3, visibility_down(&input, 4, 0));
+        assert_eq!(1, visibility_down(&input, 1, 1));
+        assert_eq!(1, visibility_down(&input, 2, 3));
+    }
+
+    #[test]
+    pub fn test_visibility_left() {
+        let input = build_input(&example_input!(DayEight));
+        assert_eq!(0, visibility_left(&input, 0, 0));
+        assert_eq!(1, visibility_left(&input, 1, 0));
+        assert_eq!(1, visibility_left(&input, 1, 1));
+        assert_eq!(1, visibility_left(&input, 3, 2));
+        assert_eq!(4, visibility_left(&input, 4, 3));
+        assert_eq!(2, visibility_left(&input, 2, 3));
+    }
+
+    #[test]
+    pub fn test_visibility_right() {
+        let input = build_input(&example_input!(DayEight));
+        assert_eq!(0, visibility_right(&input, 4, 0));
+        assert_eq!(1, visibility_right(&input, 3, 1));
+        assert_eq!(1, visibility_right(&input, 3, 2));
+        assert_eq!(1, visibility_right(&input, 2, 2));
+        assert_eq!(1, visibility_right(&input, 2, 2));
+        assert_eq!(2, visibility_right(&input, 2, 3));
     }
-    0
 }
@@ -1,147 +1,411 @@
-use std::{
-    borrow::Borrow, collections::HashMap, num::ParseIntError, ops::RangeFrom, path::PathBuf,
-    str::FromStr,
-};
+use std::{fmt::Display, io::BufRead, num::ParseIntError, str::FromStr};
 
-use crate::utils::read_lines;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
-struct State {
-    stacks: Vec<Vec<char>>,
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
+
+pub mod checkpoint;
+pub mod crane;
+pub mod drawing;
+
+use crane::{Crane, MultiCrane, SingleCrane};
+use drawing::DrawingError;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct State {
+    stacks: Vec<Vec<String>>,
 }
 
 impl State {
-    fn new() -> State {
+    pub fn new() -> State {
         State { stacks: Vec::new() }
     }
 
-    fn build(input: &mut Vec<String>) -> State {
-        let mut state = State::new();
-        let mut indices: HashMap<usize, usize> = HashMap::new();
-        for (i, character) in input
-            .pop()
-            .expect("Expected column line")
-            .chars()
-            .enumerate()
-        {
-            if character != ' ' {
-                let column: usize = character
-                    .to_string()
-                    .parse()
-                    .expect("Invalid column number");
-                indices.insert(i, column - 1);
+    /// Reads the puzzle's drawing of the stacks, the column numbers last.
+    pub fn build(lines: &[String]) -> Result<State, DrawingError> {
+        Ok(State {
+            stacks: drawing::parse(lines)?,
+        })
+    }
+
+    /// Moves crates lift by lift, so a crane that lifts fewer crates than
+    /// the move asks for takes several trips.
+    pub fn implement_move(
+        &mut self,
+        move_struct: &Move,
+        crane: &dyn Crane,
+    ) -> Result<(), MoveError> {
+        for stack in [move_struct.from, move_struct.to] {
+            if stack >= self.stacks.len() {
+                return Err(MoveError::NoStack { stack: stack + 1 });
             }
         }
-        for line in input {
-            for (i, character) in line.chars().enumerate() {
-                if indices.contains_key(&i) && character != ' ' {
-                    if let Some(column) = indices.get(&i) {
-                        state.add_item(*column, character);
-                    }
-                }
-            }
+        let held = self.stacks[move_struct.from].len();
+        if held < move_struct.count {
+            return Err(MoveError::NotEnoughCrates {
+                stack: move_struct.from + 1,
+                count: move_struct.count,
+                held,
+            });
         }
-        state.finish_construction();
-        state
-    }
-
-    fn add_stack(&mut self) {
-        self.stacks.push(Vec::new());
-    }
-
-    fn finish_construction(&mut self) {
-        for stack in self.stacks.iter_mut() {
-            stack.reverse();
+        let capacity = crane.capacity().unwrap_or(move_struct.count).max(1);
+        let mut remaining = move_struct.count;
+        let mut lifted = Vec::with_capacity(capacity.min(remaining));
+        while remaining > 0 {
+            let from = &mut self.stacks[move_struct.from];
+            let start = from.len() - capacity.min(remaining);
+            lifted.extend(from.drain(start..));
+            remaining -= lifted.len();
+            crane.place(&mut lifted, &mut self.stacks[move_struct.to]);
+            lifted.clear();
         }
+        Ok(())
     }
 
-    fn add_item(&mut self, stack: usize, item: char) {
-        while self.stacks.len() <= stack {
-            self.add_stack();
+    pub fn final_state(&self) -> String {
+        let mut result: String = "".to_string();
+        for stack in &self.stacks {
+            if !stack.is_empty() {
+                result += stack.last().unwrap();
+            }
         }
-        self.stacks
-            .get_mut(stack)
-            .expect("Out of bounds stack")
-            .push(item);
+        result
     }
+}
 
-    fn implement_move(&mut self, move_struct: &Move, bonus: bool) {
-        let from = self.stacks.get(move_struct.from).expect("Out of range");
-        let range: RangeFrom<usize> = RangeFrom {
-            start: from.len() - move_struct.count,
-        };
-        let mut intermediate: Vec<char> = self
+/// Draws the stacks the way the puzzle does, so the output can be read back
+/// with `State::build`. Columns widen to fit long labels and numbers.
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let widths: Vec<usize> = self
             .stacks
-            .get_mut(move_struct.from)
-            .expect("Out of range")
-            .drain(range)
+            .iter()
+            .enumerate()
+            .map(|(index, stack)| {
+                stack
+                    .iter()
+                    .map(|item| item.chars().count() + 2)
+                    .chain([3, (index + 1).to_string().len()])
+                    .max()
+                    .unwrap_or_default()
+            })
             .collect();
-        if !bonus {
-            intermediate.reverse();
-        }
-        let target = self.stacks.get_mut(move_struct.to).expect("Out of range");
-        for val in intermediate {
-            target.push(val);
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let cells: Vec<String> = self
+                .stacks
+                .iter()
+                .zip(&widths)
+                .map(|(stack, width)| match stack.get(level) {
+                    Some(item) => format!("{:^width$}", format!("[{}]", item)),
+                    None => " ".repeat(*width),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
         }
+        let columns: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(index, width)| format!("{:^width$}", index + 1))
+            .collect();
+        writeln!(f, "{}", columns.join(" "))
     }
+}
 
-    fn final_state(&self) -> String {
-        let mut result: String = "".to_string();
-        for stack in &self.stacks {
-            if !stack.is_empty() {
-                result += &stack.last().unwrap().to_string();
-            }
+/// Why a move could not be carried out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    /// The move names a stack the drawing does not have, numbered from 1.
+    NoStack { stack: usize },
+    /// The stack holds fewer crates than the move asks for.
+    NotEnoughCrates {
+        stack: usize,
+        count: usize,
+        held: usize,
+    },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::NoStack { stack } => write!(f, "there is no stack {}", stack),
+            MoveError::NotEnoughCrates { stack, count, held } => write!(
+                f,
+                "cannot move {} crates from stack {} holding {}",
+                count, stack, held
+            ),
         }
-        result
     }
 }
 
-#[derive(Debug)]
-struct Move {
+/// Stacks are numbered from 1 when serialized, as in the puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "MoveRecord", into = "MoveRecord")]
+pub struct Move {
     from: usize,
     to: usize,
     count: usize,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MoveRecord {
+    count: usize,
+    from: usize,
+    to: usize,
+}
+
+impl TryFrom<MoveRecord> for Move {
+    type Error = String;
+
+    fn try_from(record: MoveRecord) -> Result<Self, Self::Error> {
+        if record.from == 0 || record.to == 0 {
+            return Err("stacks are numbered from 1".to_string());
+        }
+        Ok(Move {
+            from: record.from - 1,
+            to: record.to - 1,
+            count: record.count,
+        })
+    }
+}
+
+impl From<Move> for MoveRecord {
+    fn from(move_struct: Move) -> Self {
+        MoveRecord {
+            count: move_struct.count,
+            from: move_struct.from + 1,
+            to: move_struct.to + 1,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
 impl FromStr for Move {
-    type Err = ParseIntError;
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (a, b, c) = s
             .strip_prefix("move ")
             .and_then(|s| s.split_once(" from "))
             .and_then(|(a, s)| s.split_once(" to ").map(|(b, c)| (a, b, c)))
-            .unwrap();
+            .ok_or_else(|| "expected 'move N from A to B'".to_string())?;
+        let parse_stack = |stack: &str| match stack.parse::<usize>() {
+            Ok(0) => Err("stacks are numbered from 1".to_string()),
+            Ok(number) => Ok(number - 1),
+            Err(err) => Err(format!("invalid stack '{}': {}", stack, err)),
+        };
         Ok(Move {
-            from: b.parse::<usize>()? - 1,
-            to: c.parse::<usize>()? - 1,
-            count: a.parse::<usize>()?,
+            from: parse_stack(b)?,
+            to: parse_stack(c)?,
+            count: a
+                .parse::<usize>()
+                .map_err(|err: ParseIntError| format!("invalid count '{}': {}", a, err))?,
         })
     }
 }
 
-pub fn run(path: &PathBuf, bonus: bool) -> String {
-    let mut state = State::new();
-    if let Ok(lines) = read_lines(path) {
+/// Carries out every move with the given crane, starting from `initial`.
+pub fn simulate(initial: &State, moves: &[Move], crane: &dyn Crane) -> Result<State> {
+    replay(initial, moves, crane, |_, _| {})
+}
+
+/// Like `simulate`, but hands the state to `step` after each move along
+/// with the 1-based number of the move.
+pub fn replay(
+    initial: &State,
+    moves: &[Move],
+    crane: &dyn Crane,
+    mut step: impl FnMut(usize, &State),
+) -> Result<State> {
+    let mut state = initial.clone();
+    for (index, move_struct) in moves.iter().enumerate() {
+        state
+            .implement_move(move_struct, crane)
+            .map_err(|err| Error::semantic(format!("move {}: {}", index + 1, err)))?;
+        step(index + 1, &state);
+    }
+    Ok(state)
+}
+
+pub struct DayFive;
+
+impl Solution for DayFive {
+    type Input = (State, Vec<Move>);
+    type Answer = String;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
+        let mut state = None;
+        let mut moves = Vec::new();
         let mut initial_state: Vec<String> = Vec::new();
-        let mut finalized = false;
-        for maybe_line in lines {
-            if let Ok(line) = maybe_line {
-                if line == "" {
-                    finalized = true;
-                    state = State::build(&mut initial_state);
-                } else if finalized {
-                    state.implement_move(
-                        Move::from_str(line.as_str())
-                            .expect("Invalid input")
-                            .borrow(),
-                        bonus,
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            if state.is_some() {
+                if !line.trim().is_empty() {
+                    moves.push(
+                        Move::from_str(line.trim())
+                            .map_err(|err| Error::parse(index + 1, &line, err))?,
                     );
-                } else {
-                    initial_state.push(line);
                 }
+            } else if line.trim().is_empty() {
+                // The drawing starts on the first line of the input.
+                state = Some(State::build(&initial_state).map_err(|err| {
+                    let text = initial_state.get(err.line).cloned().unwrap_or_default();
+                    Error::parse(err.line + 1, &text, err)
+                })?);
+            } else {
+                initial_state.push(line);
             }
         }
+        let state = state.ok_or_else(|| {
+            Error::semantic("expected a blank line between the stack drawing and the moves")
+        })?;
+        Ok((state, moves))
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(simulate(&input.0, &input.1, &SingleCrane)?.final_state())
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(simulate(&input.0, &input.1, &MultiCrane)?.final_state())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../fixtures/5.txt"),
+            part_one: Some("CMZ"),
+            part_two: Some("MCD"),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crane::{BottomCrane, LimitedCrane};
+    use super::*;
+    use crate::solution::{example_input, test_examples};
+
+    test_examples!(DayFive);
+
+    #[test]
+    fn test_move_from_empty_stack() {
+        let mut state = State::build(&["[A]    ".to_string(), " 1   2 ".to_string()]).unwrap();
+        let move_struct = Move::from_str("move 2 from 1 to 2").unwrap();
+        assert_eq!(
+            Err(MoveError::NotEnoughCrates {
+                stack: 1,
+                count: 2,
+                held: 1
+            }),
+            state.implement_move(&move_struct, &SingleCrane)
+        );
+        assert_eq!("A", state.final_state());
+        let move_struct = Move::from_str("move 1 from 2 to 1").unwrap();
+        assert!(state.implement_move(&move_struct, &MultiCrane).is_err());
+        let move_struct = Move::from_str("move 1 from 1 to 3").unwrap();
+        assert_eq!(
+            Err(MoveError::NoStack { stack: 3 }),
+            state.implement_move(&move_struct, &MultiCrane)
+        );
+    }
+
+    #[test]
+    fn test_cranes() {
+        let (state, _) = example_input!(DayFive);
+        let move_struct = Move::from_str("move 3 from 2 to 1").unwrap();
+        let top = |crane: &dyn Crane| {
+            let mut state = state.clone();
+            state.implement_move(&move_struct, crane).unwrap();
+            state.final_state()
+        };
+        // Stack 2 holds M, C, D from the bottom up.
+        assert_eq!("MP", top(&SingleCrane));
+        assert_eq!("DP", top(&MultiCrane));
+        // Two lifts: C, D first, then M on top of them.
+        assert_eq!("MP", top(&LimitedCrane::new(2).unwrap()));
+        assert_eq!("NP", top(&BottomCrane));
+    }
+
+    #[test]
+    fn test_move_onto_same_stack() {
+        let (state, _) = example_input!(DayFive);
+        let move_struct = Move::from_str("move 2 from 1 to 1").unwrap();
+        for crane in [&SingleCrane as &dyn Crane, &MultiCrane] {
+            let mut moved = state.clone();
+            moved.implement_move(&move_struct, crane).unwrap();
+            assert_eq!(state.final_state(), moved.final_state());
+        }
+    }
+
+    fn drawing(state: &State) -> Vec<String> {
+        state.to_string().lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let (state, _) = example_input!(DayFive);
+        let expected: Vec<&str> = include_str!("../../fixtures/5.txt")
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .collect();
+        assert_eq!(expected, drawing(&state));
+        assert_eq!(vec![String::new()], drawing(&State::new()));
+    }
+
+    #[test]
+    fn test_render_round_trip() {
+        let (state, moves) = example_input!(DayFive);
+        assert_eq!(state, State::build(&drawing(&state)).unwrap());
+        let mut steps = 0;
+        replay(&state, &moves, &SingleCrane, |_, state| {
+            assert_eq!(*state, State::build(&drawing(state)).unwrap());
+            steps += 1;
+        })
+        .unwrap();
+        assert_eq!(moves.len(), steps);
+    }
+
+    #[test]
+    fn test_render_wide_round_trip() {
+        let mut stacks: Vec<Vec<String>> = (0..11).map(|_| vec!["X".to_string()]).collect();
+        stacks[3].push("LONG".to_string());
+        stacks[10].push("Q12".to_string());
+        let state = State { stacks };
+        assert_eq!(state, State::build(&drawing(&state)).unwrap());
+        assert_eq!("XXXLONGXXXXXXQ12", state.final_state());
+    }
+
+    #[test]
+    fn test_drawing_error_position() {
+        let input = "[A]\n[B] [C\n 1   2\n\nmove 1 from 1 to 2\n";
+        match DayFive.parse(&mut input.as_bytes()) {
+            Err(Error::Parse { line, message, .. }) => {
+                assert_eq!(2, line);
+                assert_eq!("column 5: crate is never closed", message);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_move() {
+        assert!(Move::from_str("move 1 from 0 to 2").is_err());
+        assert!(Move::from_str("move one from 1 to 2").is_err());
+        let move_struct = Move::from_str("move 3 from 1 to 12").unwrap();
+        assert_eq!("move 3 from 1 to 12", move_struct.to_string());
     }
-    state.final_state()
 }
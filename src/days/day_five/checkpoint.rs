@@ -0,0 +1,146 @@
+use std::{fmt::Display, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Move, State};
+use crate::error::{Error, Result};
+
+/// A simulation paused part way: the stacks so far and the moves still to
+/// make. Tools can also write one by hand to start from any state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Checkpoint {
+    /// Moves already carried out to reach `state`.
+    #[serde(default)]
+    pub done: usize,
+    pub state: State,
+    #[serde(default)]
+    pub moves: Vec<Move>,
+}
+
+impl Checkpoint {
+    pub fn new(state: State, moves: Vec<Move>) -> Checkpoint {
+        Checkpoint {
+            done: 0,
+            state,
+            moves,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Checkpoint, String> {
+        serde_json::from_str(json).map_err(|err| err.to_string())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("checkpoints always serialize")
+    }
+
+    pub fn load(path: &Path) -> Result<Checkpoint> {
+        Checkpoint::from_json(&fs::read_to_string(path)?)
+            .map_err(|err| Error::semantic(format!("{}: {}", path.display(), err)))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Ok(fs::write(path, self.to_json() + "\n")?)
+    }
+}
+
+/// A stack that differs between two states, crates listed bottom first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackDiff {
+    /// Numbered from 1, as in the drawing.
+    pub stack: usize,
+    pub left: Vec<String>,
+    pub right: Vec<String>,
+}
+
+impl Display for StackDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Single letters run together as in the puzzle's answers, longer
+        // labels are kept apart.
+        let crates = |stack: &[String]| -> String {
+            if stack.is_empty() {
+                "(empty)".to_string()
+            } else if stack.iter().all(|item| item.chars().count() == 1) {
+                stack.concat()
+            } else {
+                stack.join(" ")
+            }
+        };
+        write!(
+            f,
+            "stack {}: {} | {}",
+            self.stack,
+            crates(&self.left),
+            crates(&self.right)
+        )
+    }
+}
+
+/// Every stack whose crates differ, a stack only one state has counting as
+/// empty in the other.
+pub fn diff(left: &State, right: &State) -> Vec<StackDiff> {
+    let count = left.stacks.len().max(right.stacks.len());
+    let stack = |state: &State, index: usize| state.stacks.get(index).cloned().unwrap_or_default();
+    (0..count)
+        .map(|index| StackDiff {
+            stack: index + 1,
+            left: stack(left, index),
+            right: stack(right, index),
+        })
+        .filter(|diff| diff.left != diff.right)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::day_five::{crane::SingleCrane, simulate, DayFive};
+    use crate::solution::example_input;
+
+    #[test]
+    fn test_round_trip() {
+        let (state, moves) = example_input!(DayFive);
+        let checkpoint = Checkpoint::new(state, moves);
+        assert_eq!(
+            checkpoint,
+            Checkpoint::from_json(&checkpoint.to_json()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_json() {
+        let checkpoint = Checkpoint::from_json(
+            r#"{"state": {"stacks": [["Z", "N"], ["M", "C", "D"], ["P"]]},
+                "moves": [{"count": 1, "from": 2, "to": 1}]}"#,
+        )
+        .unwrap();
+        let (state, moves) = example_input!(DayFive);
+        assert_eq!(state, checkpoint.state);
+        assert_eq!(moves[..1], checkpoint.moves[..]);
+        let wide = Checkpoint::from_json(r#"{"state": {"stacks": [["AB", "C"]]}}"#).unwrap();
+        assert_eq!(
+            "stack 1: AB C | (empty)",
+            diff(&wide.state, &State::new())[0].to_string()
+        );
+        assert!(Checkpoint::from_json(
+            r#"{"state": {"stacks": []}, "moves": [{"count": 1, "from": 0, "to": 1}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let (state, moves) = example_input!(DayFive);
+        let moved = simulate(&state, &moves[..1], &SingleCrane).unwrap();
+        let diffs = diff(&state, &moved);
+        assert_eq!(2, diffs.len());
+        assert_eq!("stack 1: ZN | ZND", diffs[0].to_string());
+        assert_eq!("stack 2: MCD | MC", diffs[1].to_string());
+        assert!(diff(&state, &state).is_empty());
+        assert_eq!(
+            "stack 3: P | (empty)",
+            diff(&state, &State::build(&[" 1   2 ".to_string()]).unwrap())[2].to_string()
+        );
+    }
+}
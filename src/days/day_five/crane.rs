@@ -0,0 +1,140 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A crane moving crates between stacks, one lift at a time.
+pub trait Crane {
+    /// The most crates picked up in one lift, `None` if there is no limit.
+    fn capacity(&self) -> Option<usize>;
+
+    /// Puts the crates of one lift, bottom crate first, down on a stack.
+    fn place(&self, lifted: &mut Vec<String>, to: &mut Vec<String>) {
+        to.append(lifted);
+    }
+}
+
+/// The CrateMover 9000, which lifts one crate at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SingleCrane;
+
+impl Crane for SingleCrane {
+    fn capacity(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// The CrateMover 9001, which lifts any number of crates at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultiCrane;
+
+impl Crane for MultiCrane {
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Lifts at most a fixed number of crates at once, keeping their order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitedCrane {
+    capacity: usize,
+}
+
+impl LimitedCrane {
+    pub fn new(capacity: usize) -> Option<LimitedCrane> {
+        (capacity > 0).then_some(LimitedCrane { capacity })
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn capacity(&self) -> Option<usize> {
+        Some(self.capacity)
+    }
+}
+
+/// Lifts any number of crates at once and slides them under the
+/// destination stack instead of on top of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BottomCrane;
+
+impl Crane for BottomCrane {
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    fn place(&self, lifted: &mut Vec<String>, to: &mut Vec<String>) {
+        to.splice(0..0, lifted.drain(..));
+    }
+}
+
+/// A crane picked by name, as on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    Single,
+    Multi,
+    Limited(LimitedCrane),
+    Bottom,
+}
+
+impl Model {
+    pub fn crane(&self) -> &dyn Crane {
+        match self {
+            Model::Single => &SingleCrane,
+            Model::Multi => &MultiCrane,
+            Model::Limited(crane) => crane,
+            Model::Bottom => &BottomCrane,
+        }
+    }
+}
+
+impl FromStr for Model {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" | "9000" => Ok(Model::Single),
+            "multi" | "9001" => Ok(Model::Multi),
+            "bottom" => Ok(Model::Bottom),
+            _ => s
+                .strip_prefix("limited:")
+                .and_then(|capacity| capacity.parse::<usize>().ok())
+                .and_then(LimitedCrane::new)
+                .map(Model::Limited)
+                .ok_or_else(|| {
+                    format!(
+                        "unknown crane '{}', expected single, multi, bottom or limited:K with K > 0",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Model::Single => write!(f, "single"),
+            Model::Multi => write!(f, "multi"),
+            Model::Limited(crane) => write!(f, "limited:{}", crane.capacity),
+            Model::Bottom => write!(f, "bottom"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_model() {
+        assert_eq!(Ok(Model::Single), Model::from_str("9000"));
+        assert_eq!(Ok(Model::Multi), Model::from_str("multi"));
+        assert_eq!(
+            Some(3),
+            Model::from_str("limited:3").unwrap().crane().capacity()
+        );
+        assert_eq!(
+            "limited:3",
+            Model::from_str("limited:3").unwrap().to_string()
+        );
+        assert!(Model::from_str("limited:0").is_err());
+        assert!(Model::from_str("9002").is_err());
+    }
+}
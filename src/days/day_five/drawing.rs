@@ -0,0 +1,297 @@
+use std::fmt::Display;
+
+/// Columns a tab advances to the next multiple of, once the indentation
+/// every line shares has been removed.
+const TAB_WIDTH: usize = 8;
+
+/// A problem in the stack drawing, at a 0-based line of the drawing and a
+/// 1-based character column of that line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrawingError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl DrawingError {
+    fn new(line: usize, column: usize, message: impl Display) -> DrawingError {
+        DrawingError {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for DrawingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+/// A run of the drawing: a crate label or a column number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    /// Display positions the token covers, after expanding tabs.
+    start: usize,
+    end: usize,
+    /// The 1-based character column the token starts at in the line.
+    column: usize,
+    text: String,
+}
+
+impl Token {
+    fn overlaps(&self, other: &Token) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// The characters of a line with the display position and the original
+/// character column of each, tabs expanded.
+fn layout(line: &str, indent: usize) -> Vec<(usize, usize, char)> {
+    let mut position = 0;
+    let mut cells = Vec::new();
+    for (index, character) in line.chars().enumerate().skip(indent) {
+        cells.push((position, index + 1, character));
+        position = match character {
+            '\t' => (position / TAB_WIDTH + 1) * TAB_WIDTH,
+            _ => position + 1,
+        };
+    }
+    cells
+}
+
+/// How many leading characters every non-blank line shares, so an indented
+/// drawing lines up the same as an unindented one.
+fn common_indent(lines: &[String]) -> usize {
+    let mut lines = lines.iter().filter(|line| !line.trim().is_empty());
+    let first = match lines.next() {
+        Some(first) => first,
+        None => return 0,
+    };
+    let mut indent: Vec<char> = first.chars().take_while(|c| c.is_whitespace()).collect();
+    for line in lines {
+        let shared = line
+            .chars()
+            .zip(&indent)
+            .take_while(|(a, b)| a == *b)
+            .count();
+        indent.truncate(shared);
+    }
+    indent.len()
+}
+
+/// Splits a line of crates into bracketed labels.
+fn crates(line: &str, indent: usize, index: usize) -> Result<Vec<Token>, DrawingError> {
+    let cells = layout(line, indent);
+    let mut tokens = Vec::new();
+    let mut cells = cells.iter();
+    while let Some(&(start, column, character)) = cells.next() {
+        match character {
+            _ if character.is_whitespace() => {}
+            '[' => {
+                let mut text = String::new();
+                let end = loop {
+                    match cells.next() {
+                        Some(&(end, _, ']')) => break end + 1,
+                        Some(&(_, column, '[')) => {
+                            return Err(DrawingError::new(
+                                index,
+                                column,
+                                "unexpected '[' in a crate",
+                            ))
+                        }
+                        Some(&(_, _, character)) => text.push(character),
+                        None => {
+                            return Err(DrawingError::new(index, column, "crate is never closed"))
+                        }
+                    }
+                };
+                if text.trim().is_empty() {
+                    return Err(DrawingError::new(index, column, "crate has no label"));
+                }
+                tokens.push(Token {
+                    start,
+                    end,
+                    column,
+                    text,
+                });
+            }
+            _ => {
+                return Err(DrawingError::new(
+                    index,
+                    column,
+                    format!("unexpected '{}', crates are drawn as [label]", character),
+                ))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Splits the last line of the drawing into column numbers, which have to
+/// count up from 1.
+fn columns(line: &str, indent: usize, index: usize) -> Result<Vec<Token>, DrawingError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    for &(position, column, character) in &layout(line, indent) {
+        match tokens.last_mut() {
+            _ if character.is_whitespace() => continue,
+            Some(token) if token.end == position => {
+                token.end += 1;
+                token.text.push(character);
+            }
+            _ => tokens.push(Token {
+                start: position,
+                end: position + 1,
+                column,
+                text: character.to_string(),
+            }),
+        }
+    }
+    for (number, token) in tokens.iter().enumerate() {
+        if token.text.parse::<usize>() != Ok(number + 1) {
+            return Err(DrawingError::new(
+                index,
+                token.column,
+                format!("expected column {}, found '{}'", number + 1, token.text),
+            ));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads a drawing of stacked crates above a line of column numbers into
+/// stacks listed bottom first.
+pub fn parse(lines: &[String]) -> Result<Vec<Vec<String>>, DrawingError> {
+    let (column_line, crate_lines) = lines
+        .split_last()
+        .ok_or_else(|| DrawingError::new(0, 1, "expected a column number line"))?;
+    let indent = common_indent(lines);
+    let numbers = columns(column_line, indent, crate_lines.len())?;
+    let mut stacks: Vec<Vec<String>> = vec![Vec::new(); numbers.len()];
+    for (index, line) in crate_lines.iter().enumerate().rev() {
+        let mut filled = vec![false; numbers.len()];
+        for token in crates(line, indent, index)? {
+            let below: Vec<usize> = (0..numbers.len())
+                .filter(|&column| numbers[column].overlaps(&token))
+                .collect();
+            let column = match below[..] {
+                [column] => column,
+                [] => {
+                    return Err(DrawingError::new(
+                        index,
+                        token.column,
+                        format!("crate [{}] is not above a column number", token.text),
+                    ))
+                }
+                [first, second, ..] => {
+                    return Err(DrawingError::new(
+                        index,
+                        token.column,
+                        format!(
+                            "crate [{}] spans columns {} and {}",
+                            token.text,
+                            first + 1,
+                            second + 1
+                        ),
+                    ))
+                }
+            };
+            if filled[column] {
+                return Err(DrawingError::new(
+                    index,
+                    token.column,
+                    format!("second crate above column {} on one line", column + 1),
+                ));
+            }
+            if stacks[column].len() + index + 1 != crate_lines.len() {
+                return Err(DrawingError::new(
+                    index,
+                    token.column,
+                    format!(
+                        "crate [{}] floats above a gap in column {}",
+                        token.text,
+                        column + 1
+                    ),
+                ));
+            }
+            filled[column] = true;
+            stacks[column].push(token.text);
+        }
+    }
+    Ok(stacks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawing(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_multi_digit_columns() {
+        let mut top = String::new();
+        let mut numbers = String::new();
+        for column in 1..=12 {
+            top.push_str(&format!("[{}] ", (b'A' + column as u8 - 1) as char));
+            numbers.push_str(&format!("{:^3} ", column));
+        }
+        let stacks = parse(&[top, numbers]).unwrap();
+        assert_eq!(12, stacks.len());
+        assert_eq!(vec!["L".to_string()], stacks[11]);
+    }
+
+    #[test]
+    fn test_wide_labels() {
+        let stacks = parse(&drawing("      [Q7]\n[ABC] [X]\n  1    2")).unwrap();
+        assert_eq!(vec![vec!["ABC"], vec!["X", "Q7"]], stacks);
+    }
+
+    #[test]
+    fn test_ragged_and_indented() {
+        // Trailing whitespace trimmed away and both lines indented by a tab.
+        let stacks = parse(&drawing("\t    [D]\n\t[N] [C]  \n\t 1   2   3")).unwrap();
+        assert_eq!(vec![vec!["N"], vec!["C", "D"], vec![]], stacks);
+        let stacks = parse(&drawing("\t\t[D]\n\t[N]\t[C]\n\t 1\t 2")).unwrap();
+        assert_eq!(vec![vec!["N"], vec!["C", "D"]], stacks);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |text: &str| parse(&drawing(text)).unwrap_err();
+        assert_eq!(
+            DrawingError::new(0, 1, "expected a column number line"),
+            parse(&[]).unwrap_err()
+        );
+        assert_eq!(
+            DrawingError::new(1, 6, "expected column 2, found '3'"),
+            error("[A]\n 1   3")
+        );
+        assert_eq!(
+            DrawingError::new(0, 5, "crate is never closed"),
+            error("[A] [B\n 1   2")
+        );
+        assert_eq!(
+            DrawingError::new(0, 1, "crate has no label"),
+            error("[]\n 1")
+        );
+        assert_eq!(
+            DrawingError::new(0, 3, "unexpected 'x', crates are drawn as [label]"),
+            error("  x\n 1")
+        );
+        assert_eq!(
+            DrawingError::new(0, 9, "crate [C] is not above a column number"),
+            error("[A]     [C]\n 1   2")
+        );
+        assert_eq!(
+            DrawingError::new(0, 1, "crate [ABCDE] spans columns 1 and 2"),
+            error("[ABCDE]\n 1   2")
+        );
+        assert_eq!(
+            DrawingError::new(0, 1, "crate [A] floats above a gap in column 1"),
+            error("[A]\n    [B]\n 1   2")
+        );
+    }
+}
@@ -1,46 +1,62 @@
-use std::{num::ParseIntError, path::PathBuf, str::FromStr};
+use std::{io::BufRead, num::ParseIntError, str::FromStr};
 
-use crate::utils::read_lines;
+use crate::error::{Error, Result};
+use crate::interval::{self, Interval, IntervalSet};
+use crate::solution::{Example, Solution};
 
-struct Assignment {
-    start: u8,
-    end: u8,
+pub mod roster;
+
+/// The sections one elf is assigned, from the first to the last inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assignment {
+    sections: Interval<u32>,
 }
 
 impl FromStr for Assignment {
-    type Err = ParseIntError;
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once('-').expect("should contain one dash");
-
-        Ok(Assignment {
-            start: start.parse::<u8>()?,
-            end: end.parse::<u8>()?,
-        })
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("assignment '{}' should contain one dash", s))?;
+        let parse_bound = |bound: &str| {
+            bound
+                .parse::<u32>()
+                .map_err(|err: ParseIntError| format!("invalid section '{}': {}", bound, err))
+        };
+        let sections = Interval::new(parse_bound(start)?, parse_bound(end)?)
+            .ok_or_else(|| format!("assignment '{}' ends before it starts", s))?;
+        Ok(Assignment { sections })
     }
 }
 
 impl Assignment {
-    fn contains(&self, other: &Assignment) -> bool {
-        self.start <= other.start && self.end >= other.end
+    pub fn sections(&self) -> Interval<u32> {
+        self.sections
+    }
+
+    pub fn contains(&self, other: &Assignment) -> bool {
+        self.sections.covers(&other.sections)
     }
 
-    fn overlap(&self, other: &Assignment) -> bool {
-        (self.start >= other.start && self.start <= other.end)
-            || (self.end >= other.start && self.end <= other.end)
+    pub fn overlap(&self, other: &Assignment) -> bool {
+        self.sections.overlaps(&other.sections)
     }
 }
 
-struct Pair {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair {
     first: Assignment,
     second: Assignment,
 }
 
 impl FromStr for Pair {
-    type Err = ParseIntError;
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (first, second) = s.split_once(",").expect("Should contain one comma");
+        let (first, second) = s
+            .split_once(',')
+            .ok_or_else(|| "pair should contain one comma".to_string())?;
         Ok(Pair {
             first: Assignment::from_str(first)?,
             second: Assignment::from_str(second)?,
@@ -49,20 +65,92 @@ impl FromStr for Pair {
 }
 
 impl Pair {
-    fn full_overlap(&self) -> bool {
+    pub fn full_overlap(&self) -> bool {
         self.first.contains(&self.second) || self.second.contains(&self.first)
     }
 
-    fn overlap(&self) -> bool {
-        self.first.overlap(&self.second) || self.second.overlap(&self.first)
+    pub fn overlap(&self) -> bool {
+        self.first.overlap(&self.second)
+    }
+
+    pub fn assignments(&self) -> [Assignment; 2] {
+        [self.first, self.second]
+    }
+}
+
+fn all_sections(pairs: &[Pair]) -> impl Iterator<Item = Interval<u32>> + '_ {
+    pairs
+        .iter()
+        .flat_map(Pair::assignments)
+        .map(|assignment| assignment.sections())
+}
+
+/// Every section at least one elf is assigned.
+pub fn sections_covered(pairs: &[Pair]) -> IntervalSet<u32> {
+    all_sections(pairs).collect()
+}
+
+/// Every section assigned to more than one elf, whether or not they share a
+/// line.
+pub fn sections_shared(pairs: &[Pair]) -> IntervalSet<u32> {
+    let sections: Vec<Interval<u32>> = all_sections(pairs).collect();
+    interval::depths(&sections)
+        .into_iter()
+        .filter(|(_, depth)| *depth > 1)
+        .map(|(run, _)| run)
+        .collect()
+}
+
+/// The most elves assigned the same section.
+pub fn max_overlap_depth(pairs: &[Pair]) -> usize {
+    let sections: Vec<Interval<u32>> = all_sections(pairs).collect();
+    interval::max_depth(&sections)
+}
+
+pub struct DayFour;
+
+impl Solution for DayFour {
+    type Input = Vec<Pair>;
+    type Answer = usize;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
+        let mut pairs = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            let pair =
+                Pair::from_str(line.as_str()).map_err(|err| Error::parse(index + 1, &line, err))?;
+            pairs.push(pair);
+        }
+        Ok(pairs)
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(input.iter().filter(|pair| pair.full_overlap()).count())
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(input.iter().filter(|pair| pair.overlap()).count())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../fixtures/4.txt"),
+            part_one: Some("2"),
+            part_two: Some("4"),
+        }]
     }
 }
 
 #[cfg(test)]
+// The expected value is spelled out next to each pair of sections.
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use std::borrow::Borrow;
 
     use super::*;
+    use crate::solution::{example_input, test_examples};
+
+    test_examples!(DayFour);
 
     #[test]
     fn test_contain() {
@@ -124,29 +212,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_invalid_assignment() {
+        assert!(Assignment::from_str("3").is_err());
+        assert!(Assignment::from_str("7-3").is_err());
+        assert!(Pair::from_str("2-8;3-7").is_err());
+    }
+
     #[test]
     fn test_duplicated() {
         assert_eq!(true, Pair::from_str("2-8,3-7").unwrap().full_overlap());
         assert_eq!(true, Pair::from_str("6-6,4-6").unwrap().full_overlap());
         assert_eq!(false, Pair::from_str("2-6,4-8").unwrap().full_overlap());
     }
-}
 
-pub fn run(path: &PathBuf, bonus: bool) -> u32 {
-    let mut count = 0;
-    if let Ok(lines) = read_lines(path) {
-        for maybe_line in lines {
-            if let Ok(line) = maybe_line {
-                if let Ok(pair) = Pair::from_str(line.as_str()) {
-                    if bonus && pair.overlap() {
-                        count += 1
-                    }
-                    if !bonus && pair.full_overlap() {
-                        count += 1;
-                    }
-                }
-            }
-        }
+    #[test]
+    fn test_wide_sections() {
+        assert!(Pair::from_str("200-100000,99999-1000000")
+            .unwrap()
+            .overlap());
+    }
+
+    #[test]
+    fn test_roster_queries() {
+        let pairs = example_input!(DayFour);
+        assert_eq!("2-9", sections_covered(&pairs).to_string());
+        assert_eq!(8, sections_covered(&pairs).len());
+        assert_eq!("2-8", sections_shared(&pairs).to_string());
+        // Section 6 is assigned to eight of the twelve elves.
+        assert_eq!(8, max_overlap_depth(&pairs));
     }
-    count
 }
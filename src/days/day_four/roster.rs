@@ -0,0 +1,293 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::interval::{self, Interval};
+
+use super::Pair;
+
+/// How one elf's assignment relates to everyone else's.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ElfCoverage {
+    /// 1-based input line.
+    pub line: usize,
+    /// 1 or 2, the elf's position on its line.
+    pub elf: u8,
+    pub sections: Interval<u32>,
+    /// Elves on other lines sharing at least one section with this one.
+    pub overlapping: usize,
+    /// The other lines those elves are on, only filled in when asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Contention {
+    pub sections: Interval<u32>,
+    pub elves: usize,
+}
+
+/// Coverage of every section between the lowest and highest assigned one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    /// Runs of sections nobody is assigned.
+    pub gaps: Vec<Interval<u32>>,
+    /// The first run of sections assigned to the most elves.
+    pub most_contended: Option<Contention>,
+    pub elves: Vec<ElfCoverage>,
+}
+
+/// Sweeps over every assignment in the roster. Gaps, contention and overlap
+/// counts take O(n log n); listing the overlapping lines of every elf adds
+/// time and memory proportional to the number of overlapping pairs, so it is
+/// only done when `with_lines` is set.
+pub fn coverage(pairs: &[Pair], with_lines: bool) -> Coverage {
+    let elves: Vec<(usize, u8, Interval<u32>)> = pairs
+        .iter()
+        .enumerate()
+        .flat_map(|(index, pair)| {
+            let [first, second] = pair.assignments();
+            [
+                (index + 1, 1, first.sections()),
+                (index + 1, 2, second.sections()),
+            ]
+        })
+        .collect();
+    let sections: Vec<Interval<u32>> = elves.iter().map(|(_, _, sections)| *sections).collect();
+    let (gaps, most_contended) = sweep(&sections);
+
+    let disjoint = disjoint_counts(&sections);
+    let mut lines = with_lines.then(|| overlapping_lines(&elves));
+
+    let coverage = elves
+        .iter()
+        .enumerate()
+        .map(|(index, (line, elf, sections))| {
+            // Every elf not entirely before or after this one overlaps it,
+            // which counts the elf itself and maybe its partner.
+            let partner = &elves[index ^ 1].2;
+            let same_line = 1 + usize::from(partner.overlaps(sections));
+            ElfCoverage {
+                line: *line,
+                elf: *elf,
+                sections: *sections,
+                overlapping: elves.len() - disjoint[index] - same_line,
+                lines: lines
+                    .as_mut()
+                    .map(|lines| std::mem::take(&mut lines[index])),
+            }
+        })
+        .collect();
+    Coverage {
+        gaps,
+        most_contended,
+        elves: coverage,
+    }
+}
+
+/// For each interval, how many of the others end before it starts or start
+/// after it ends. Visiting the intervals by start and then by end lets one
+/// pointer into the sorted ends, then the sorted starts, only move forwards.
+fn disjoint_counts(sections: &[Interval<u32>]) -> Vec<usize> {
+    let mut starts: Vec<u32> = sections.iter().map(Interval::start).collect();
+    let mut ends: Vec<u32> = sections.iter().map(Interval::end).collect();
+    starts.sort_unstable();
+    ends.sort_unstable();
+    let mut order: Vec<usize> = (0..sections.len()).collect();
+    let mut disjoint = vec![0; sections.len()];
+
+    order.sort_unstable_by_key(|index| sections[*index].start());
+    let mut ended = 0;
+    for &index in &order {
+        while ended < ends.len() && ends[ended] < sections[index].start() {
+            ended += 1;
+        }
+        disjoint[index] = ended;
+    }
+
+    order.sort_unstable_by_key(|index| sections[*index].end());
+    let mut started = 0;
+    for &index in &order {
+        while started < starts.len() && starts[started] <= sections[index].end() {
+            started += 1;
+        }
+        disjoint[index] += starts.len() - started;
+    }
+    disjoint
+}
+
+/// Finds the gaps and the most contended run in one pass over the runs of
+/// sections covered by the same number of elves.
+fn sweep(sections: &[Interval<u32>]) -> (Vec<Interval<u32>>, Option<Contention>) {
+    let mut gaps = Vec::new();
+    let mut most_contended: Option<Contention> = None;
+    for (run, depth) in interval::depths(sections) {
+        if depth == 0 {
+            gaps.push(run);
+        } else if most_contended.as_ref().is_none_or(|c| depth > c.elves) {
+            most_contended = Some(Contention {
+                sections: run,
+                elves: depth,
+            });
+        }
+    }
+    (gaps, most_contended)
+}
+
+/// For each elf, the sorted other lines with an elf overlapping it. Elves are
+/// visited by start while the ones still covering that start stay active, so
+/// each overlapping pair is seen exactly once.
+fn overlapping_lines(elves: &[(usize, u8, Interval<u32>)]) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..elves.len()).collect();
+    order.sort_unstable_by_key(|index| elves[*index].2.start());
+    let mut active: BTreeSet<(u32, usize)> = BTreeSet::new();
+    let mut lines = vec![Vec::new(); elves.len()];
+    for index in order {
+        let (line, _, sections) = elves[index];
+        while let Some(&(end, _)) = active.first() {
+            if end >= sections.start() {
+                break;
+            }
+            active.pop_first();
+        }
+        for &(_, other) in &active {
+            let other_line = elves[other].0;
+            if other_line != line {
+                lines[index].push(other_line);
+                lines[other].push(line);
+            }
+        }
+        active.insert((sections.end(), index));
+    }
+    for list in &mut lines {
+        list.sort_unstable();
+        list.dedup();
+    }
+    lines
+}
+
+/// Lays the coverage out as text, one row per elf after the summary.
+pub fn format_table(coverage: &Coverage) -> String {
+    let gaps: Vec<String> = coverage.gaps.iter().map(Interval::to_string).collect();
+    let mut output = format!(
+        "gaps: {}\n",
+        if gaps.is_empty() {
+            "none".to_string()
+        } else {
+            gaps.join(", ")
+        }
+    );
+    if let Some(contention) = &coverage.most_contended {
+        output.push_str(&format!(
+            "most contended: {} ({} elves)\n",
+            contention.sections, contention.elves
+        ));
+    }
+    let cells: Vec<String> = coverage
+        .elves
+        .iter()
+        .map(|elf| elf.sections.to_string())
+        .collect();
+    let width = cells
+        .iter()
+        .map(String::len)
+        .chain(["sections".len()])
+        .max()
+        .unwrap_or_default();
+    output.push_str(&format!(
+        "{:>6}  {:>3}  {:<width$}  {:>11}",
+        "line", "elf", "sections", "overlapping"
+    ));
+    let with_lines = coverage.elves.iter().any(|elf| elf.lines.is_some());
+    output.push_str(if with_lines { "  lines\n" } else { "\n" });
+    for (elf, sections) in coverage.elves.iter().zip(cells) {
+        output.push_str(&format!(
+            "{:>6}  {:>3}  {:<width$}  {:>11}",
+            elf.line, elf.elf, sections, elf.overlapping
+        ));
+        if let Some(lines) = &elf.lines {
+            let lines: Vec<String> = lines.iter().map(usize::to_string).collect();
+            output.push_str(&format!("  {}", lines.join(",")));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::day_four::DayFour;
+    use crate::solution::{example_input, Solution};
+
+    #[test]
+    fn test_coverage() {
+        let pairs = DayFour
+            .parse(&mut "1-2,10-12\n3-3,11-20\n30-30,5-5\n".as_bytes())
+            .unwrap();
+        let coverage = coverage(&pairs, true);
+        let gaps: Vec<String> = coverage.gaps.iter().map(Interval::to_string).collect();
+        assert_eq!(vec!["4-4", "6-9", "21-29"], gaps);
+        let contention = coverage.most_contended.unwrap();
+        assert_eq!("11-12", contention.sections.to_string());
+        assert_eq!(2, contention.elves);
+        let overlaps: Vec<(usize, Option<Vec<usize>>)> = coverage
+            .elves
+            .iter()
+            .map(|elf| (elf.overlapping, elf.lines.clone()))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, Some(vec![])),
+                (1, Some(vec![2])),
+                (0, Some(vec![])),
+                (1, Some(vec![1])),
+                (0, Some(vec![])),
+                (0, Some(vec![])),
+            ],
+            overlaps
+        );
+    }
+
+    #[test]
+    fn test_counts_match_lines() {
+        let pairs = example_input!(DayFour);
+        let coverage = coverage(&pairs, true);
+        assert!(coverage.gaps.is_empty());
+        assert_eq!(8, coverage.most_contended.unwrap().elves);
+        for elf in &coverage.elves {
+            let lines = elf.lines.as_ref().unwrap();
+            assert!(!lines.contains(&elf.line));
+            assert!(elf.overlapping >= lines.len());
+        }
+        // 2-4 on line 1 meets 2-3 and 4-5, 2-8 and 3-7, 4-6, 2-6 and 4-8.
+        assert_eq!(Some(vec![2, 4, 5, 6]), coverage.elves[0].lines);
+        assert_eq!(7, coverage.elves[0].overlapping);
+    }
+
+    #[test]
+    fn test_format_table() {
+        let pairs = example_input!(DayFour);
+        let table = format_table(&coverage(&pairs, false));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!("gaps: none", lines[0]);
+        assert_eq!("most contended: 6-6 (8 elves)", lines[1]);
+        assert_eq!("  line  elf  sections  overlapping", lines[2]);
+        assert_eq!("     1    1  2-4                 7", lines[3]);
+        assert_eq!(15, lines.len());
+    }
+
+    #[test]
+    fn test_large_roster() {
+        let input: String = (0..100_000u32)
+            .map(|i| format!("{}-{},{}-{}\n", i * 3, i * 3 + 1, i * 3 + 1, i * 3 + 4))
+            .collect();
+        let pairs = DayFour.parse(&mut input.as_bytes()).unwrap();
+        let coverage = coverage(&pairs, true);
+        // Each line's second elf meets both elves of the next line.
+        assert!(coverage.gaps.is_empty());
+        assert_eq!(3, coverage.most_contended.unwrap().elves);
+        assert_eq!(Some(vec![2]), coverage.elves[1].lines);
+        assert_eq!(3, coverage.elves[3].overlapping);
+    }
+}
@@ -1,15 +1,16 @@
-use std::{collections::HashSet, num::ParseIntError, path::PathBuf, str::FromStr};
+use std::{collections::HashSet, io::BufRead, num::ParseIntError, str::FromStr};
 
-use crate::utils::read_lines;
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
 
 #[derive(PartialEq, PartialOrd, Eq, Hash, Clone, Debug)]
-struct Coordinate {
+pub struct Coordinate {
     x: isize,
     y: isize,
 }
 
 impl Coordinate {
-    fn new(x: isize, y: isize) -> Coordinate {
+    pub fn new(x: isize, y: isize) -> Coordinate {
         Coordinate { x, y }
     }
 
@@ -27,7 +28,7 @@ impl Coordinate {
 }
 
 #[derive(Debug, Clone, Copy)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
     Up,
@@ -35,34 +36,34 @@ enum Direction {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Move {
+pub struct Move {
     direction: Direction,
     times: usize,
 }
 
 impl Move {
-    fn right(times: usize) -> Move {
+    pub fn right(times: usize) -> Move {
         Move {
             direction: Direction::Right,
             times,
         }
     }
 
-    fn left(times: usize) -> Move {
+    pub fn left(times: usize) -> Move {
         Move {
             direction: Direction::Left,
             times,
         }
     }
 
-    fn up(times: usize) -> Move {
+    pub fn up(times: usize) -> Move {
         Move {
             direction: Direction::Up,
             times,
         }
     }
 
-    fn down(times: usize) -> Move {
+    pub fn down(times: usize) -> Move {
         Move {
             direction: Direction::Down,
             times,
@@ -71,17 +72,21 @@ impl Move {
 }
 
 impl FromStr for Move {
-    type Err = ParseIntError;
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (d, n) = s.split_once(" ").expect("Should have space");
-        let times = n.parse::<usize>()?;
+        let (d, n) = s
+            .split_once(' ')
+            .ok_or_else(|| "expected a direction and a distance".to_string())?;
+        let times = n
+            .parse::<usize>()
+            .map_err(|err: ParseIntError| format!("invalid distance '{}': {}", n, err))?;
         match d {
             "R" => Ok(Move::right(times)),
             "L" => Ok(Move::left(times)),
             "U" => Ok(Move::up(times)),
             "D" => Ok(Move::down(times)),
-            _ => panic!("Unexpected input"),
+            _ => Err(format!("unknown direction '{}'", d)),
         }
     }
 }
@@ -104,18 +109,12 @@ fn calculate_shift(head: isize, tail: isize) -> isize {
 fn calculate_new_position(knot: &Coordinate, following: &Coordinate) -> Coordinate {
     let delta_x = knot.delta_x(following);
     let delta_y = knot.delta_y(following);
-    if knot == following {
-        return following.clone();
-    } else if delta_x.abs() == 1 && delta_y == 0 {
-        return following.clone();
-    } else if delta_y.abs() == 1 && delta_x == 0 {
-        return following.clone();
-    } else if delta_x.abs() == 1 && delta_y.abs() == 1 {
-        return following.clone();
+    if delta_x.abs() <= 1 && delta_y.abs() <= 1 {
+        following.clone()
     } else {
         let x_shift = calculate_shift(knot.x, following.x);
         let y_shift = calculate_shift(knot.y, following.y);
-        return following.shift(x_shift, y_shift);
+        following.shift(x_shift, y_shift)
     }
 }
 
@@ -170,9 +169,63 @@ impl Bridge {
     }
 }
 
+pub struct DayNine;
+
+impl DayNine {
+    fn simulate(input: &[Move], knot_count: usize) -> usize {
+        let mut bridge = Bridge::new(knot_count);
+        for planned_move in input {
+            bridge.process_move(planned_move);
+        }
+        bridge.max_positions()
+    }
+}
+
+impl Solution for DayNine {
+    type Input = Vec<Move>;
+    type Answer = usize;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
+        let mut moves = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            let planned_move =
+                Move::from_str(line.as_str()).map_err(|err| Error::parse(index + 1, &line, err))?;
+            moves.push(planned_move);
+        }
+        Ok(moves)
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(DayNine::simulate(input, 2))
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(DayNine::simulate(input, 10))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                input: include_str!("../../fixtures/9.txt"),
+                part_one: Some("13"),
+                part_two: Some("1"),
+            },
+            Example {
+                input: include_str!("../../fixtures/9-2.txt"),
+                part_one: None,
+                part_two: Some("36"),
+            },
+        ]
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::solution::test_examples;
+
+    test_examples!(DayNine);
 
     #[test]
     pub fn test_move_simple() {
@@ -222,21 +275,3 @@ pub mod tests {
         assert_eq!(&Coordinate::new(1, 1), bridge.tail_position());
     }
 }
-
-pub fn run(path: &PathBuf, bonus: bool) -> usize {
-    let mut bridge = if bonus {
-        Bridge::new(10)
-    } else {
-        Bridge::new(2)
-    };
-    if let Ok(lines) = read_lines(path) {
-        for maybe_line in lines {
-            if let Ok(line) = maybe_line {
-                if let Ok(planned_move) = Move::from_str(line.as_str()) {
-                    bridge.process_move(&planned_move);
-                }
-            }
-        }
-    }
-    bridge.max_positions()
-}
@@ -1,59 +1,454 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::PathBuf;
+use std::{cmp::Reverse, collections::BinaryHeap, fmt::Display, io::BufRead, str::FromStr};
 
-struct Elf {
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Elf {
     food: Vec<u32>,
+    /// Kept as food is added, which is refused if it would overflow.
+    total: u32,
 }
 
 impl Elf {
-    fn new() -> Elf {
-        Elf { food: vec![] }
+    pub fn new() -> Elf {
+        Elf {
+            food: vec![],
+            total: 0,
+        }
     }
 
-    fn add_food(&mut self, food: u32) {
+    pub fn add_food(&mut self, food: u32) -> Result<(), String> {
+        self.total = self
+            .total
+            .checked_add(food)
+            .ok_or_else(|| "elf carries more than u32::MAX calories".to_string())?;
         self.food.push(food);
+        Ok(())
+    }
+
+    pub fn total_calories(&self) -> u32 {
+        self.total
+    }
+
+    pub fn food(&self) -> &[u32] {
+        &self.food
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.food.len()
+    }
+}
+
+/// Every elf in the order they appear in the input, with statistics over
+/// their calorie totals. Order statistics use selection rather than a full
+/// sort, so they stay linear in the number of elves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    elves: Vec<Elf>,
+}
+
+impl Inventory {
+    pub fn new(elves: Vec<Elf>) -> Inventory {
+        Inventory { elves }
+    }
+
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    pub fn len(&self) -> usize {
+        self.elves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elves.is_empty()
+    }
+
+    pub fn totals(&self) -> Vec<u32> {
+        self.elves.iter().map(Elf::total_calories).collect()
+    }
+
+    pub fn item_counts(&self) -> Vec<usize> {
+        self.elves.iter().map(Elf::item_count).collect()
+    }
+
+    /// The `k` largest totals, largest first.
+    pub fn top(&self, k: usize) -> Vec<u32> {
+        let mut totals = self.totals();
+        smallest_k(&mut totals, k, |total| Reverse(*total))
+    }
+
+    /// The `k` smallest totals, smallest first.
+    pub fn bottom(&self, k: usize) -> Vec<u32> {
+        let mut totals = self.totals();
+        smallest_k(&mut totals, k, |total| *total)
+    }
+
+    /// The median total, averaging the two middle totals for an even count.
+    pub fn median(&self) -> Option<f64> {
+        let mut totals = self.totals();
+        if totals.is_empty() {
+            return None;
+        }
+        let middle = totals.len() / 2;
+        let (lower, upper, _) = totals.select_nth_unstable(middle);
+        let upper = f64::from(*upper);
+        if self.len() % 2 == 1 {
+            Some(upper)
+        } else {
+            let lower = f64::from(*lower.iter().max().expect("even count has a lower half"));
+            Some((lower + upper) / 2.0)
+        }
+    }
+
+    /// The nearest-rank `percentile` (0 to 100) of the totals.
+    pub fn percentile(&self, percentile: f64) -> Option<u32> {
+        if self.is_empty() || !(0.0..=100.0).contains(&percentile) {
+            return None;
+        }
+        let rank = (percentile / 100.0 * self.len() as f64).ceil() as usize;
+        let mut totals = self.totals();
+        Some(*totals.select_nth_unstable(rank.max(1) - 1).1)
+    }
+
+    /// Indices of the elves ordered by `key`, largest first. Elves with equal
+    /// keys keep their input order.
+    pub fn rank_by<K: Ord>(&self, mut key: impl FnMut(&Elf) -> K) -> Vec<usize> {
+        let mut ranked: Vec<(K, usize)> = self
+            .elves
+            .iter()
+            .enumerate()
+            .map(|(index, elf)| (key(elf), index))
+            .collect();
+        ranked.sort_by(|(a, i), (b, j)| b.cmp(a).then(i.cmp(j)));
+        ranked.into_iter().map(|(_, index)| index).collect()
+    }
+
+    pub fn stat(&self, stat: Stat) -> Option<f64> {
+        let totals = || self.elves.iter().map(Elf::total_calories);
+        match stat {
+            Stat::Elves => Some(self.len() as f64),
+            Stat::Min => totals().min().map(f64::from),
+            Stat::Max => totals().max().map(f64::from),
+            Stat::Sum => Some(totals().map(u64::from).sum::<u64>() as f64),
+            Stat::Mean if self.is_empty() => None,
+            Stat::Mean => Some(totals().map(f64::from).sum::<f64>() / self.len() as f64),
+            Stat::Median => self.median(),
+            Stat::Percentile(percentile) => self.percentile(percentile).map(f64::from),
+        }
+    }
+}
+
+/// Partially orders `values` so the `k` smallest by `key` come first, and
+/// returns just those in order.
+fn smallest_k<K: Ord>(values: &mut [u32], k: usize, key: impl Fn(&u32) -> K) -> Vec<u32> {
+    let k = k.min(values.len());
+    if k == 0 {
+        return vec![];
+    }
+    if k < values.len() {
+        values.select_nth_unstable_by_key(k - 1, &key);
+    }
+    let mut selected = values[..k].to_vec();
+    selected.sort_unstable_by_key(key);
+    selected
+}
+
+/// The `k` largest values pushed so far. A min-heap of at most `k` values is
+/// kept, so memory does not grow with the number of values pushed.
+#[derive(Debug, Clone)]
+pub struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<u32>>,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> TopK {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    pub fn push(&mut self, value: u32) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(value));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if value > smallest.0 {
+                *smallest = Reverse(value);
+            }
+        }
+    }
+
+    /// The values kept, largest first.
+    pub fn into_sorted_vec(self) -> Vec<u32> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(value)| value)
+            .collect()
     }
+}
 
-    fn total_calories(&self) -> u32 {
-        self.food.iter().sum()
+/// Finds the `k` largest calorie totals while reading, without keeping the
+/// elves around. Only the current line and the `k` best totals are held.
+pub fn stream_top(input: &mut dyn BufRead, k: usize) -> Result<Vec<u32>> {
+    let mut top = TopK::new(k);
+    let mut line = String::new();
+    let mut current: Option<u32> = None;
+    let mut index = 0;
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+        index += 1;
+        let text = line.trim_end_matches(['\n', '\r']);
+        if text.is_empty() {
+            if let Some(total) = current.take() {
+                top.push(total);
+            }
+        } else {
+            let calories = text
+                .parse::<u32>()
+                .map_err(|err| Error::parse(index, text, err))?;
+            let total = current.unwrap_or_default().checked_add(calories);
+            current = Some(total.ok_or_else(|| {
+                Error::parse(index, text, "elf carries more than u32::MAX calories")
+            })?);
+        }
+    }
+    if let Some(total) = current {
+        top.push(total);
     }
+    Ok(top.into_sorted_vec())
+}
+
+/// A summary statistic over the elves' calorie totals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stat {
+    Elves,
+    Min,
+    Max,
+    Sum,
+    Mean,
+    Median,
+    /// Written as `p90` for the 90th percentile.
+    Percentile(f64),
 }
 
-pub fn run(path: &PathBuf, bonus: bool) -> u32 {
-    let mut elves: Vec<Elf> = vec![];
-    let mut current_elf = Elf::new();
-    if let Ok(lines) = read_lines(path) {
-        // Consumes the iterator, returns an (Optional) String
-        for line in lines {
-            if let Ok(ip) = line {
-                if ip == "" {
+impl FromStr for Stat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "elves" => Ok(Stat::Elves),
+            "min" => Ok(Stat::Min),
+            "max" => Ok(Stat::Max),
+            "sum" => Ok(Stat::Sum),
+            "mean" => Ok(Stat::Mean),
+            "median" => Ok(Stat::Median),
+            _ => s
+                .strip_prefix('p')
+                .and_then(|p| p.parse::<f64>().ok())
+                .filter(|p| (0.0..=100.0).contains(p))
+                .map(Stat::Percentile)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown statistic '{}', expected elves, min, max, sum, mean, median or p0 to p100",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+impl Display for Stat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stat::Elves => write!(f, "elves"),
+            Stat::Min => write!(f, "min"),
+            Stat::Max => write!(f, "max"),
+            Stat::Sum => write!(f, "sum"),
+            Stat::Mean => write!(f, "mean"),
+            Stat::Median => write!(f, "median"),
+            Stat::Percentile(percentile) => write!(f, "p{}", percentile),
+        }
+    }
+}
+
+pub struct DayOne;
+
+impl Solution for DayOne {
+    type Input = Inventory;
+    type Answer = u32;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
+        let mut elves: Vec<Elf> = vec![];
+        let mut current_elf = Elf::new();
+        for (index, line) in input.lines().enumerate() {
+            let ip = line?;
+            if ip.is_empty() {
+                // Leading or repeated blank lines do not make empty elves.
+                if current_elf.item_count() > 0 {
                     elves.push(current_elf);
                     current_elf = Elf::new();
-                } else {
-                    // Panic is ok here
-                    let calories = ip.parse::<u32>().unwrap();
-                    current_elf.add_food(calories);
                 }
+            } else {
+                let calories = ip
+                    .parse::<u32>()
+                    .map_err(|err| Error::parse(index + 1, &ip, err))?;
+                current_elf
+                    .add_food(calories)
+                    .map_err(|err| Error::parse(index + 1, &ip, err))?;
             }
         }
-        if bonus {
-            elves.sort_by_key(|elf| elf.total_calories());
-            elves.reverse();
-            elves.iter().take(3).map(|elf| elf.total_calories()).sum()
-        } else {
-            elves
-                .iter()
-                .max_by_key(|elf| elf.total_calories())
-                .expect("expected some elves to be provided")
-                .total_calories()
+        if current_elf.item_count() > 0 {
+            elves.push(current_elf);
         }
-    } else {
-        0
+        Ok(Inventory::new(elves))
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        input
+            .top(1)
+            .first()
+            .copied()
+            .ok_or_else(|| Error::semantic("expected some elves to be provided"))
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        input
+            .top(3)
+            .iter()
+            .try_fold(0u32, |sum, total| sum.checked_add(*total))
+            .ok_or_else(|| Error::semantic("the top three elves carry more than u32::MAX calories"))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../fixtures/1.txt"),
+            part_one: Some("24000"),
+            part_two: Some("45000"),
+        }]
     }
 }
 
-fn read_lines(filename: &PathBuf) -> io::Result<io::Lines<io::BufReader<File>>> {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{example_input, test_examples};
+
+    test_examples!(DayOne);
+
+    fn inventory_of(totals: &[&[u32]]) -> Inventory {
+        Inventory::new(
+            totals
+                .iter()
+                .map(|food| {
+                    let mut elf = Elf::new();
+                    for calories in *food {
+                        elf.add_food(*calories).unwrap();
+                    }
+                    elf
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_top_and_bottom() {
+        let inventory = example_input!(DayOne);
+        assert_eq!(vec![24000, 11000], inventory.top(2));
+        assert_eq!(vec![4000, 6000, 10000], inventory.bottom(3));
+        assert_eq!(5, inventory.top(10).len());
+        assert!(inventory.top(0).is_empty());
+        assert_eq!(vec![3, 1, 2, 3, 1], inventory.item_counts());
+    }
+
+    #[test]
+    fn test_order_statistics() {
+        let inventory = example_input!(DayOne);
+        assert_eq!(Some(10000.0), inventory.median());
+        assert_eq!(Some(4000), inventory.percentile(0.0));
+        assert_eq!(Some(6000), inventory.percentile(40.0));
+        assert_eq!(Some(24000), inventory.percentile(100.0));
+        assert_eq!(None, inventory.percentile(101.0));
+        assert_eq!(Some(2.5), inventory_of(&[&[1], &[4], &[2], &[3]]).median());
+        assert_eq!(None, Inventory::default().median());
+    }
+
+    #[test]
+    fn test_rank_by() {
+        let inventory = example_input!(DayOne);
+        assert_eq!(vec![3, 2, 4, 0, 1], inventory.rank_by(Elf::total_calories));
+        assert_eq!(vec![0, 3, 2, 1, 4], inventory.rank_by(Elf::item_count));
+    }
+
+    #[test]
+    fn test_last_elf_without_trailing_blank_line() {
+        let inventory = DayOne.parse(&mut "1\n\n2\n3".as_bytes()).unwrap();
+        assert_eq!(vec![1, 5], inventory.totals());
+        let inventory = DayOne.parse(&mut "1\n\n2\n3\n\n".as_bytes()).unwrap();
+        assert_eq!(vec![1, 5], inventory.totals());
+    }
+
+    #[test]
+    fn test_stream_top() {
+        let example = DayOne.examples()[0].input;
+        assert_eq!(
+            example_input!(DayOne).top(3),
+            stream_top(&mut example.as_bytes(), 3).unwrap()
+        );
+        assert_eq!(
+            vec![5, 1],
+            stream_top(&mut "1\r\n\r\n2\r\n3".as_bytes(), 5).unwrap()
+        );
+        assert!(stream_top(&mut "1\n\nfive".as_bytes(), 1).is_err());
+        assert!(stream_top(&mut "4294967295\n1".as_bytes(), 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_agrees_with_stream() {
+        // Both paths read CRLF input, skip runs of blank lines and refuse an
+        // overflowing elf alike.
+        for input in ["1\r\n\r\n2\r\n3", "1\n\n\n2", "\n1\n\n2\n\n"] {
+            let inventory = DayOne.parse(&mut input.as_bytes()).unwrap();
+            let streamed = stream_top(&mut input.as_bytes(), 3).unwrap();
+            assert_eq!(streamed, inventory.top(3), "{:?}", input);
+            assert_eq!(streamed.len(), inventory.len(), "{:?}", input);
+        }
+        match DayOne.parse(&mut "4294967295\n1".as_bytes()) {
+            Err(Error::Parse { line, .. }) => assert_eq!(2, line),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        let big = DayOne
+            .parse(&mut "4294967295\n\n4294967295\n\n1".as_bytes())
+            .unwrap();
+        assert!(DayOne.part_two(&big).is_err());
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut top = TopK::new(2);
+        for value in [5, 1, 9, 3, 9] {
+            top.push(value);
+        }
+        assert_eq!(vec![9, 9], top.into_sorted_vec());
+        let mut none = TopK::new(0);
+        none.push(1);
+        assert!(none.into_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn test_stat() {
+        let inventory = example_input!(DayOne);
+        assert_eq!(Some(11000.0), inventory.stat(Stat::Mean));
+        assert_eq!(Some(55000.0), inventory.stat(Stat::Sum));
+        assert_eq!(Ok(Stat::Percentile(90.0)), Stat::from_str("p90"));
+        assert_eq!(Ok(Stat::Median), Stat::from_str("median"));
+        assert!(Stat::from_str("p101").is_err());
+        assert!(Stat::from_str("mode").is_err());
+    }
 }
@@ -1,160 +1,149 @@
-use std::{
-    fs,
-    io::{BufReader, Lines},
-    path::PathBuf,
-};
-
-use crate::utils::read_lines;
-
-trait FileSize {
-    fn get_size(&self) -> usize;
+use std::io::BufRead;
+
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
+
+pub mod filesystem;
+
+use filesystem::{FileSystem, NodeId};
+
+/// Rebuilds the file system a terminal transcript explored. Commands run
+/// from the root until the first `cd`.
+pub fn read_transcript(input: &mut dyn BufRead) -> Result<FileSystem> {
+    let mut fs = FileSystem::new();
+    let mut current: NodeId = FileSystem::ROOT;
+    let mut listing = false;
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let error = |err: String| Error::parse(index + 1, &line, err);
+        if let Some(command) = line.strip_prefix("$ ") {
+            listing = command == "ls";
+            if let Some(path) = command.strip_prefix("cd ") {
+                current = fs.change_directory(current, path).map_err(error)?;
+            } else if !listing {
+                return Err(error("unknown command".to_string()));
+            }
+        } else if line.is_empty() {
+            continue;
+        } else if !listing {
+            return Err(error("output without an 'ls' before it".to_string()));
+        } else if let Some(name) = line.strip_prefix("dir ") {
+            fs.add_directory(current, name).map_err(error)?;
+        } else {
+            let (size, name) = line
+                .split_once(' ')
+                .ok_or_else(|| error("expected a size and a file name".to_string()))?;
+            let size = size
+                .parse::<usize>()
+                .map_err(|err| error(format!("invalid file size: {}", err)))?;
+            fs.add_file(current, name, size).map_err(error)?;
+        }
+    }
+    Ok(fs)
 }
 
-#[derive(Debug)]
-struct File {
-    #[allow(dead_code)]
-    name: String,
-    size: usize,
+pub fn sum_directories(fs: &FileSystem, limit: usize) -> usize {
+    let sizes = fs.sizes();
+    fs.directories()
+        .map(|id| sizes[id])
+        .filter(|size| *size < limit)
+        .sum()
 }
 
-impl File {
-    fn new(name: String, size: usize) -> File {
-        File { name, size }
-    }
+pub fn smallest_directory(fs: &FileSystem, limit: usize) -> Option<usize> {
+    let sizes = fs.sizes();
+    fs.directories()
+        .map(|id| sizes[id])
+        .filter(|size| *size > limit)
+        .min()
 }
 
-impl FileSize for File {
-    fn get_size(&self) -> usize {
-        self.size
-    }
-}
+pub struct DaySeven;
 
-#[derive(Debug)]
-struct Directory{
-    #[allow(dead_code)]
-    name: String,
-    files: Vec<File>,
-    descendants: Vec<Directory>,
-}
+impl Solution for DaySeven {
+    type Input = FileSystem;
+    type Answer = usize;
 
-impl Directory {
-    fn new(name: String) -> Directory {
-        Directory {
-            name,
-            files: Vec::new(),
-            descendants: Vec::new(),
-        }
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
+        read_transcript(input)
     }
 
-    fn add_file(&mut self, file: File) {
-        self.files.push(file);
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(sum_directories(input, 100_000))
     }
 
-    fn add_directory(&mut self, directory: Directory) {
-        self.descendants.push(directory);
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        let disk_size: usize = 70_000_000;
+        let used = input.size(FileSystem::ROOT);
+        let unused = disk_size.checked_sub(used).ok_or_else(|| {
+            Error::semantic(format!(
+                "files use {} which is more than the disk size of {}",
+                used, disk_size
+            ))
+        })?;
+        let target_size: usize = 30_000_000;
+        let to_delete = target_size.checked_sub(unused).ok_or_else(|| {
+            Error::semantic(format!("{} is already unused, nothing to delete", unused))
+        })?;
+        smallest_directory(input, to_delete)
+            .ok_or_else(|| Error::semantic(format!("no directory is larger than {}", to_delete)))
     }
 
-    fn get_sizes(&self) -> Vec<usize> {
-        let mut results = Vec::new();
-        results.push(self.get_size());
-        for dir in self.descendants.iter() {
-            results.append(&mut dir.get_sizes());
-        }
-        results
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../fixtures/7.txt"),
+            part_one: Some("95437"),
+            part_two: Some("24933642"),
+        }]
     }
 }
 
-impl FileSize for Directory {
-    fn get_size(&self) -> usize {
-        let file_sizes: usize = self.files.iter().map(FileSize::get_size).sum();
-        let dir_sizes: usize = self.descendants.iter().map(|f| f.get_size()).sum();
-        file_sizes + dir_sizes
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{example_input, test_examples};
 
-fn handle_file(directory: &mut Directory, line: String) {
-    if let Some((size_str, filename)) = line.split_once(" ") {
-        if let Ok(size) = size_str.parse::<usize>() {
-            let file = File::new(filename.to_string(), size);
-            directory.add_file(file);
-        } else {
-            panic!("Invalid file size {}", line);
-        }
-    } else {
-        panic!("Invalid file string {}", line);
-    }
-}
+    test_examples!(DaySeven);
 
-fn build_directory(name: String, lines: &mut Lines<BufReader<fs::File>>) -> Directory {
-    let mut directory = Directory::new(name);
-    loop {
-        if let Some(Ok(line)) = lines.next() {
-            if line == "$ cd .." {
-                break;
-            } else if line == "$ ls" {
-                continue;
-            } else if line.starts_with("$ cd") {
-                let dir_name = line.clone().remove("$ cd ".len()).to_string();
-                directory.add_directory(build_directory(dir_name, lines));
-            } else if line.starts_with("dir") {
-                continue;
-            } else {
-                handle_file(&mut directory, line);
-            }
-        } else {
-            break;
-        }
+    fn transcript(text: &str) -> Result<FileSystem> {
+        read_transcript(&mut text.as_bytes())
     }
-    directory
-}
 
-fn handle_line(mut lines: Lines<BufReader<fs::File>>) -> Directory {
-    if let Some(Ok(line)) = lines.next() {
-        if line != "$ cd /" {
-            panic!("Unexpected first cd command '{}'", line);
-        } else {
-            return build_directory("/".to_string(), &mut lines);
-        }
-    } else {
-        panic!("Could not read file");
+    #[test]
+    fn test_example_tree() {
+        let fs = example_input!(DaySeven);
+        let sizes = fs.sizes();
+        assert_eq!(48381165, sizes[FileSystem::ROOT]);
+        assert_eq!(Some(584), fs.lookup("/a/e").map(|id| sizes[id]));
+        assert_eq!(Some(94853), fs.lookup("/a").map(|id| fs.size(id)));
+        assert_eq!(Some(24933642), fs.lookup("/d").map(|id| sizes[id]));
+        assert_eq!(4, fs.directories().count());
     }
-}
 
-fn sum_directories(directory: &Directory, limit: usize) -> usize {
-    let mut sum = 0;
-    if directory.get_size() < limit {
-        sum += directory.get_size();
+    #[test]
+    fn test_navigation() {
+        let fs = transcript(
+            "$ cd /\n$ ls\ndir logs\n$ cd logs/old\n$ ls\n10 a.log\n\
+             $ cd /\n$ cd logs\n$ ls\ndir old\n20 b.log\n$ cd old\n$ ls\n10 a.log\n",
+        )
+        .unwrap();
+        assert_eq!(Some(30), fs.lookup("/logs").map(|id| fs.size(id)));
+        assert_eq!(Some(10), fs.lookup("/logs/old/a.log").map(|id| fs.size(id)));
+        // The root, two directories and two files, the second listing of
+        // old adding nothing.
+        assert_eq!(5, fs.len());
     }
-    let descendants: &Vec<Directory> = &directory.descendants;
-    sum += descendants
-        .into_iter()
-        .map(|d| sum_directories(&d, limit))
-        .sum::<usize>();
-    sum
-}
 
-fn smallest_directory(directory: &Directory, limit: usize) -> usize {
-    let mut sizes = directory.get_sizes();
-    sizes.sort();
-    sizes
-        .into_iter()
-        .filter(|s| s.clone().clone() > limit)
-        .next()
-        .unwrap_or(0)
-}
-
-pub fn run(path: &PathBuf, bonus: bool) -> usize {
-    if let Ok(lines) = read_lines(path) {
-        let root = handle_line(lines);
-        if bonus {
-            let disk_size = 70_000_000;
-            let unused = disk_size - root.get_size();
-            let target_size = 30_000_000;
-            let to_delete = target_size - unused;
-            return smallest_directory(&root, to_delete);
-        } else {
-            return sum_directories(&root, 100_000);
-        }
+    #[test]
+    fn test_transcript_errors() {
+        let line = |text: &str| match transcript(text) {
+            Err(Error::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!(2, line("$ cd /\n$ rm -rf a\n"));
+        assert_eq!(2, line("$ cd /\n10 a\n"));
+        assert_eq!(3, line("$ ls\n10 a\nten b\n"));
+        assert_eq!(4, line("$ ls\n10 a\n$ ls\n11 a\n"));
+        assert_eq!(3, line("$ ls\n10 a\n$ cd a\n"));
     }
-    0
 }
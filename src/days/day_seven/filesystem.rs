@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+
+/// Index of an entry in its `FileSystem`.
+pub type NodeId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    File { size: usize },
+    Directory { children: BTreeMap<String, NodeId> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    name: String,
+    /// `None` only for the root directory.
+    parent: Option<NodeId>,
+    kind: Kind,
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+
+    pub fn is_directory(&self) -> bool {
+        matches!(self.kind, Kind::Directory { .. })
+    }
+}
+
+/// A directory tree kept in one arena, entries referring to each other by
+/// index. Entries are only ever added, so a parent always comes before its
+/// children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    entries: Vec<Entry>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem::new()
+    }
+}
+
+impl FileSystem {
+    pub const ROOT: NodeId = 0;
+
+    /// A file system holding nothing but an empty root directory.
+    pub fn new() -> FileSystem {
+        FileSystem {
+            entries: vec![Entry {
+                name: String::new(),
+                parent: None,
+                kind: Kind::Directory {
+                    children: BTreeMap::new(),
+                },
+            }],
+        }
+    }
+
+    pub fn entry(&self, id: NodeId) -> &Entry {
+        &self.entries[id]
+    }
+
+    /// Entries including the root directory.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there is nothing but the root directory.
+    pub fn is_empty(&self) -> bool {
+        self.entries.len() == 1
+    }
+
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let children = match &self.entries[id].kind {
+            Kind::Directory { children } => Some(children.values().copied()),
+            Kind::File { .. } => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.entries.len()).filter(|id| self.entries[*id].is_directory())
+    }
+
+    fn child(&self, directory: NodeId, name: &str) -> Result<Option<NodeId>, String> {
+        match &self.entries[directory].kind {
+            Kind::Directory { children } => Ok(children.get(name).copied()),
+            Kind::File { .. } => Err(format!("{} is not a directory", self.path(directory))),
+        }
+    }
+
+    fn insert(&mut self, directory: NodeId, name: &str, kind: Kind) -> Result<NodeId, String> {
+        if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+            return Err(format!("invalid name '{}'", name));
+        }
+        let id = self.entries.len();
+        self.entries.push(Entry {
+            name: name.to_string(),
+            parent: Some(directory),
+            kind,
+        });
+        if let Kind::Directory { children } = &mut self.entries[directory].kind {
+            children.insert(name.to_string(), id);
+        }
+        Ok(id)
+    }
+
+    /// Adds a directory, or returns the one already there, so listing a
+    /// directory twice changes nothing.
+    pub fn add_directory(&mut self, directory: NodeId, name: &str) -> Result<NodeId, String> {
+        match self.child(directory, name)? {
+            Some(id) if self.entries[id].is_directory() => Ok(id),
+            Some(id) => Err(format!("{} is a file, not a directory", self.path(id))),
+            None => self.insert(
+                directory,
+                name,
+                Kind::Directory {
+                    children: BTreeMap::new(),
+                },
+            ),
+        }
+    }
+
+    /// Adds a file, or returns the one already there if it has the same size.
+    pub fn add_file(
+        &mut self,
+        directory: NodeId,
+        name: &str,
+        size: usize,
+    ) -> Result<NodeId, String> {
+        match self.child(directory, name)? {
+            None => self.insert(directory, name, Kind::File { size }),
+            Some(id) => match self.entries[id].kind {
+                Kind::File { size: known } if known == size => Ok(id),
+                Kind::File { size: known } => Err(format!(
+                    "{} was listed with size {} and now {}",
+                    self.path(id),
+                    known,
+                    size
+                )),
+                Kind::Directory { .. } => {
+                    Err(format!("{} is a directory, not a file", self.path(id)))
+                }
+            },
+        }
+    }
+
+    /// Follows `path` from `from` the way `cd` would: a leading `/` starts
+    /// at the root, `..` climbs (staying put at the root) and directories
+    /// not seen yet are added, since `cd` proves they exist.
+    pub fn change_directory(&mut self, from: NodeId, path: &str) -> Result<NodeId, String> {
+        let mut current = if path.starts_with('/') {
+            FileSystem::ROOT
+        } else {
+            from
+        };
+        for part in path.split('/').filter(|part| !part.is_empty()) {
+            current = match part {
+                "." => current,
+                ".." => self.entries[current].parent.unwrap_or(FileSystem::ROOT),
+                name => self.add_directory(current, name)?,
+            };
+        }
+        Ok(current)
+    }
+
+    /// Finds the entry at an absolute path such as `/a/e/i`.
+    pub fn lookup(&self, path: &str) -> Option<NodeId> {
+        let mut current = FileSystem::ROOT;
+        for part in path.strip_prefix('/')?.split('/') {
+            current = match part {
+                "" | "." => current,
+                ".." => self.entries[current].parent.unwrap_or(FileSystem::ROOT),
+                name => self.child(current, name).ok()??,
+            };
+        }
+        Some(current)
+    }
+
+    /// The absolute path of an entry, `/` for the root.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.entries[current].parent {
+            names.push(self.entries[current].name.as_str());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// The size of every entry, indexed by `NodeId`, a directory's being the
+    /// total of everything below it.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self
+            .entries
+            .iter()
+            .map(|entry| match entry.kind {
+                Kind::File { size } => size,
+                Kind::Directory { .. } => 0,
+            })
+            .collect();
+        // Children always come after their parent.
+        for id in (1..self.entries.len()).rev() {
+            if let Some(parent) = self.entries[id].parent {
+                sizes[parent] += sizes[id];
+            }
+        }
+        sizes
+    }
+
+    pub fn size(&self, id: NodeId) -> usize {
+        match &self.entries[id].kind {
+            Kind::File { size } => *size,
+            Kind::Directory { .. } => self.children(id).map(|child| self.size(child)).sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_directory() {
+        let mut fs = FileSystem::new();
+        let b = fs.change_directory(FileSystem::ROOT, "a/b").unwrap();
+        assert_eq!("/a/b", fs.path(b));
+        let a = fs.change_directory(b, "..").unwrap();
+        assert_eq!(Some(a), fs.lookup("/a"));
+        assert_eq!(FileSystem::ROOT, fs.change_directory(b, "/").unwrap());
+        assert_eq!(
+            FileSystem::ROOT,
+            fs.change_directory(FileSystem::ROOT, "..").unwrap()
+        );
+        assert_eq!(b, fs.change_directory(a, "/a/./b").unwrap());
+        assert_eq!(3, fs.len());
+    }
+
+    #[test]
+    fn test_listing_twice() {
+        let mut fs = FileSystem::new();
+        let a = fs.add_directory(FileSystem::ROOT, "a").unwrap();
+        let f = fs.add_file(a, "f", 10).unwrap();
+        assert_eq!(Ok(a), fs.add_directory(FileSystem::ROOT, "a"));
+        assert_eq!(Ok(f), fs.add_file(a, "f", 10));
+        assert!(fs.add_file(a, "f", 11).is_err());
+        assert!(fs.add_file(FileSystem::ROOT, "a", 1).is_err());
+        assert!(fs.add_directory(a, "f").is_err());
+        assert!(fs.change_directory(a, "f/g").is_err());
+        assert_eq!(vec![10, 10, 10], fs.sizes());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut fs = FileSystem::new();
+        let e = fs.change_directory(FileSystem::ROOT, "/a/e").unwrap();
+        let i = fs.add_file(e, "i", 584).unwrap();
+        assert_eq!(Some(FileSystem::ROOT), fs.lookup("/"));
+        assert_eq!(Some(i), fs.lookup("/a/e/i"));
+        assert_eq!(Some(e), fs.lookup("/a/e/"));
+        assert_eq!(None, fs.lookup("/a/x"));
+        assert_eq!(None, fs.lookup("/a/e/i/j"));
+        assert_eq!(None, fs.lookup("a/e"));
+        assert_eq!(584, fs.size(FileSystem::ROOT));
+    }
+}
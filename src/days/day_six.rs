@@ -1,43 +1,162 @@
-use std::{collections::VecDeque, path::PathBuf};
+use std::{collections::VecDeque, io::BufRead};
 
-use itertools::Itertools;
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
 
-use crate::utils::read_lines;
+pub mod stream;
 
-fn find_start_packet_index(input: String, header_size: usize) -> usize {
-    let mut ring_buffer: VecDeque<char> = VecDeque::new();
-    for (index, character) in input.chars().enumerate() {
-        ring_buffer.push_front(character);
-        ring_buffer.truncate(header_size);
-        let char_count = ring_buffer.iter().unique().count();
-        if char_count == header_size {
-            return index + 1;
+/// Distinct characters that start a packet.
+pub const PACKET_MARKER: usize = 4;
+/// Distinct characters that start a message.
+pub const MESSAGE_MARKER: usize = 14;
+
+/// Watches the last `window` bytes of a signal for a run of distinct
+/// bytes, counting each byte so every step is O(1) whatever the window.
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    window: usize,
+    recent: VecDeque<u8>,
+    counts: [usize; 256],
+    /// Byte values seen more than once in the window.
+    repeated: usize,
+    consumed: usize,
+}
+
+impl MarkerDetector {
+    /// A detector for runs of `window` distinct bytes, `None` for an empty
+    /// window.
+    pub fn new(window: usize) -> Option<MarkerDetector> {
+        (window > 0).then(|| MarkerDetector {
+            window,
+            recent: VecDeque::with_capacity(window),
+            counts: [0; 256],
+            repeated: 0,
+            consumed: 0,
+        })
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Bytes pushed since the detector was created or last reset.
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Adds the next byte, returning whether the last `window` bytes are
+    /// now all different.
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.recent.len() == self.window {
+            let oldest = self.recent.pop_front().expect("the window is full");
+            self.counts[oldest as usize] -= 1;
+            if self.counts[oldest as usize] == 1 {
+                self.repeated -= 1;
+            }
+        }
+        self.recent.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.repeated += 1;
+        }
+        self.consumed += 1;
+        self.recent.len() == self.window && self.repeated == 0
+    }
+
+    /// Forgets every byte seen, as if freshly created.
+    pub fn reset(&mut self) {
+        self.recent.clear();
+        self.counts = [0; 256];
+        self.repeated = 0;
+        self.consumed = 0;
+    }
+}
+
+/// How many bytes have been read when the first run of `window` distinct
+/// bytes ends, `None` if there is no such run or the window is empty.
+pub fn find_marker(input: &[u8], window: usize) -> Option<usize> {
+    let mut detector = MarkerDetector::new(window)?;
+    input
+        .iter()
+        .position(|byte| detector.push(*byte))
+        .map(|index| index + 1)
+}
+
+fn marker(input: &str, window: usize, name: &str) -> Result<usize> {
+    find_marker(input.as_bytes(), window)
+        .ok_or_else(|| Error::semantic(format!("no start-of-{} marker in the signal", name)))
+}
+
+pub struct DaySix;
+
+impl Solution for DaySix {
+    type Input = String;
+    type Answer = usize;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
+        match input.lines().next() {
+            Some(line) => Ok(line?),
+            None => Err(Error::semantic("expected a non-empty file")),
         }
     }
-    input.len()
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        marker(input, PACKET_MARKER, "packet")
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        marker(input, MESSAGE_MARKER, "message")
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                input: include_str!("../../fixtures/6.txt"),
+                part_one: Some("7"),
+                part_two: Some("19"),
+            },
+            Example {
+                input: include_str!("../../fixtures/6-2.txt"),
+                part_one: Some("5"),
+                part_two: Some("23"),
+            },
+            Example {
+                input: include_str!("../../fixtures/6-3.txt"),
+                part_one: Some("6"),
+                part_two: Some("23"),
+            },
+            Example {
+                input: include_str!("../../fixtures/6-4.txt"),
+                part_one: Some("10"),
+                part_two: Some("29"),
+            },
+            Example {
+                input: include_str!("../../fixtures/6-5.txt"),
+                part_one: Some("11"),
+                part_two: Some("26"),
+            },
+        ]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::test_examples;
+
+    test_examples!(DaySix);
 
     #[test]
     pub fn test_start_packet_index() {
+        assert_eq!(Some(5), find_marker(b"bvwbjplbgvbhsrlpgdmjqwftvncz", 4));
+        assert_eq!(Some(6), find_marker(b"nppdvjthqldpwncqszvftbrmjlhg", 4));
         assert_eq!(
-            5,
-            find_start_packet_index("bvwbjplbgvbhsrlpgdmjqwftvncz".to_string(), 4)
+            Some(10),
+            find_marker(b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 4)
         );
         assert_eq!(
-            6,
-            find_start_packet_index("nppdvjthqldpwncqszvftbrmjlhg".to_string(), 4)
-        );
-        assert_eq!(
-            10,
-            find_start_packet_index("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string(), 4)
-        );
-        assert_eq!(
-            11,
-            find_start_packet_index("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string(), 4)
+            Some(11),
+            find_marker(b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 4)
         );
     }
 
@@ -45,29 +164,46 @@ mod tests {
     pub fn test_start_message() {
         let header_size = 14;
         assert_eq!(
-            19,
-            find_start_packet_index("mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string(), header_size)
+            Some(19),
+            find_marker(b"mjqjpqmgbljsphdztnvjfqwrcgsmlb", header_size)
         );
         assert_eq!(
-            23,
-            find_start_packet_index("bvwbjplbgvbhsrlpgdmjqwftvncz".to_string(), header_size)
+            Some(23),
+            find_marker(b"bvwbjplbgvbhsrlpgdmjqwftvncz", header_size)
         );
         assert_eq!(
-            23,
-            find_start_packet_index("nppdvjthqldpwncqszvftbrmjlhg".to_string(), header_size)
+            Some(23),
+            find_marker(b"nppdvjthqldpwncqszvftbrmjlhg", header_size)
         );
     }
-}
 
-pub fn run(path: &PathBuf, bonus: bool) -> usize {
-    if let Ok(lines) = read_lines(path) {
-        if let Some(Ok(input)) = lines.into_iter().next() {
-            let header_size = if bonus { 14 } else { 4 };
-            find_start_packet_index(input, header_size)
-        } else {
-            panic!("Expected a non-empty file");
-        }
-    } else {
-        panic!("Expected a readable file")
+    #[test]
+    fn test_no_marker() {
+        assert_eq!(None, find_marker(b"abcabcabc", 4));
+        assert_eq!(None, find_marker(b"abc", 4));
+        assert_eq!(None, find_marker(b"abcd", 0));
+        assert!(DaySix.part_one(&"aaaaaaaa".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_any_bytes() {
+        assert_eq!(Some(4), find_marker(&[0, 255, 0, 7, 200], 3));
+        assert_eq!(Some(1), find_marker(&[9, 9], 1));
+        let all: Vec<u8> = (0..=255).chain(0..=255).collect();
+        assert_eq!(Some(256), find_marker(&all, 256));
+        assert_eq!(None, find_marker(&all, 257));
+    }
+
+    #[test]
+    fn test_detector_reset() {
+        let mut detector = MarkerDetector::new(2).unwrap();
+        assert!(!detector.push(b'a'));
+        assert!(!detector.push(b'a'));
+        assert!(detector.push(b'b'));
+        assert_eq!(3, detector.consumed());
+        detector.reset();
+        assert!(!detector.push(b'b'));
+        assert!(detector.push(b'a'));
+        assert_eq!(2, detector.consumed());
     }
 }
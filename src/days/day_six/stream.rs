@@ -0,0 +1,237 @@
+use std::{fmt::Display, io::BufRead};
+
+use super::{MarkerDetector, MESSAGE_MARKER, PACKET_MARKER};
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Packet,
+    Message,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Packet => write!(f, "packet"),
+            Kind::Message => write!(f, "message"),
+        }
+    }
+}
+
+/// Something found in the signal, as a half-open range of byte offsets.
+/// Line breaks are not part of the signal and are not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// A run of distinct bytes, `end` being the puzzle's answer.
+    Marker {
+        kind: Kind,
+        start: usize,
+        end: usize,
+    },
+    /// Bytes between one marker and the next, or the end of the signal.
+    Payload { start: usize, end: usize },
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::Marker { kind, start, end } => {
+                write!(f, "start-of-{} {}..{}", kind, start, end)
+            }
+            Event::Payload { start, end } => write!(f, "payload {}..{}", start, end),
+        }
+    }
+}
+
+/// Finds every marker in a signal fed to it a byte at a time. Once a
+/// marker is found the search for the next one of its kind starts afresh,
+/// so markers of one kind never share bytes.
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    detectors: Vec<(Kind, MarkerDetector)>,
+    offset: usize,
+    /// Where the last marker found ended, and so the next payload starts.
+    payload: usize,
+}
+
+impl Decoder {
+    /// A decoder for markers of the given sizes, `None` if either is 0.
+    pub fn new(packet: usize, message: usize) -> Option<Decoder> {
+        Some(Decoder {
+            detectors: vec![
+                (Kind::Packet, MarkerDetector::new(packet)?),
+                (Kind::Message, MarkerDetector::new(message)?),
+            ],
+            offset: 0,
+            payload: 0,
+        })
+    }
+
+    /// Signal bytes seen so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn push(&mut self, byte: u8, on_event: &mut dyn FnMut(Event)) {
+        if byte == b'\n' || byte == b'\r' {
+            return;
+        }
+        self.offset += 1;
+        for (kind, detector) in self.detectors.iter_mut() {
+            if !detector.push(byte) {
+                continue;
+            }
+            let start = self.offset - detector.window();
+            detector.reset();
+            if start > self.payload {
+                on_event(Event::Payload {
+                    start: self.payload,
+                    end: start,
+                });
+            }
+            on_event(Event::Marker {
+                kind: *kind,
+                start,
+                end: self.offset,
+            });
+            self.payload = self.payload.max(self.offset);
+        }
+    }
+
+    /// Reports whatever payload follows the last marker.
+    pub fn finish(&mut self, on_event: &mut dyn FnMut(Event)) {
+        if self.offset > self.payload {
+            on_event(Event::Payload {
+                start: self.payload,
+                end: self.offset,
+            });
+            self.payload = self.offset;
+        }
+    }
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Decoder::new(PACKET_MARKER, MESSAGE_MARKER).expect("marker sizes are not 0")
+    }
+}
+
+/// Runs the decoder over everything `input` yields, a buffer at a time,
+/// returning the length of the signal.
+pub fn decode(
+    decoder: &mut Decoder,
+    input: &mut dyn BufRead,
+    mut on_event: impl FnMut(Event),
+) -> Result<usize> {
+    loop {
+        let buffer = input.fill_buf()?;
+        if buffer.is_empty() {
+            break;
+        }
+        for byte in buffer {
+            decoder.push(*byte, &mut on_event);
+        }
+        let read = buffer.len();
+        input.consume(read);
+    }
+    decoder.finish(&mut on_event);
+    Ok(decoder.offset())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Read};
+
+    use super::*;
+
+    fn events(input: &[u8], packet: usize, message: usize) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut decoder = Decoder::new(packet, message).unwrap();
+        // One byte at a time, so markers straddle every buffer boundary.
+        let mut reader = BufReader::with_capacity(1, input);
+        decode(&mut decoder, &mut reader, |event| events.push(event)).unwrap();
+        events
+    }
+
+    #[test]
+    fn test_every_marker() {
+        let packet = |start, end| Event::Marker {
+            kind: Kind::Packet,
+            start,
+            end,
+        };
+        let found = events(b"aabcaadefgaa\n", 3, 5);
+        assert_eq!(
+            vec![
+                Event::Payload { start: 0, end: 1 },
+                packet(1, 4),
+                Event::Payload { start: 4, end: 5 },
+                packet(5, 8),
+                Event::Marker {
+                    kind: Kind::Message,
+                    start: 5,
+                    end: 10
+                },
+                packet(8, 11),
+                Event::Payload { start: 11, end: 12 },
+            ],
+            found
+        );
+        assert_eq!("start-of-packet 1..4", found[1].to_string());
+        assert_eq!("payload 11..12", found[6].to_string());
+    }
+
+    #[test]
+    fn test_puzzle_answers() {
+        let input = include_str!("../../../fixtures/6.txt");
+        let found = events(input.as_bytes(), PACKET_MARKER, MESSAGE_MARKER);
+        let first = |wanted: Kind| {
+            found.iter().find_map(|event| match event {
+                Event::Marker { kind, end, .. } if *kind == wanted => Some(*end),
+                _ => None,
+            })
+        };
+        assert_eq!(Some(7), first(Kind::Packet));
+        assert_eq!(Some(19), first(Kind::Message));
+    }
+
+    #[test]
+    fn test_no_markers() {
+        assert_eq!(
+            vec![Event::Payload { start: 0, end: 6 }],
+            events(b"aaaaaa", 4, 14)
+        );
+        assert!(events(b"", 4, 14).is_empty());
+        assert!(Decoder::new(0, 14).is_none());
+    }
+
+    #[test]
+    fn test_long_stream() {
+        // A signal far larger than any buffer, never held in memory.
+        let signal = b"abcd".iter().copied().cycle().take(1 << 20);
+        let mut reader = BufReader::new(SignalReader(signal));
+        let mut markers = 0;
+        let mut decoder = Decoder::default();
+        let length = decode(&mut decoder, &mut reader, |event| {
+            if let Event::Marker { .. } = event {
+                markers += 1;
+            }
+        })
+        .unwrap();
+        assert_eq!(1 << 20, length);
+        assert_eq!((1 << 20) / 4, markers);
+    }
+
+    struct SignalReader<I>(I);
+
+    impl<I: Iterator<Item = u8>> Read for SignalReader<I> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let mut read = 0;
+            for (slot, byte) in buf.iter_mut().zip(&mut self.0) {
+                *slot = byte;
+                read += 1;
+            }
+            Ok(read)
+        }
+    }
+}
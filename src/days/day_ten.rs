@@ -1,20 +1,31 @@
-use std::{num::ParseIntError, path::PathBuf, str::FromStr};
+use std::{fmt::Display, io::BufRead, num::ParseIntError, str::FromStr};
 
-
-use crate::utils::read_lines;
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-enum Instruction {
+pub enum Instruction {
     Noop,
     AddX(isize),
 }
 
 #[derive(Debug, Clone)]
-enum InstructionError {
+pub enum InstructionError {
     UnknownInstruction(String),
     IntegerParseError,
 }
 
+impl Display for InstructionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstructionError::UnknownInstruction(instruction) => {
+                write!(f, "unknown instruction '{}'", instruction)
+            }
+            InstructionError::IntegerParseError => write!(f, "invalid addx operand"),
+        }
+    }
+}
+
 impl From<ParseIntError> for InstructionError {
     fn from(_: ParseIntError) -> Self {
         Self::IntegerParseError
@@ -31,15 +42,12 @@ impl FromStr for Instruction {
             let val = s.trim_start_matches("addx ").parse::<isize>()?;
             Ok(Self::AddX(val))
         } else {
-            Err(InstructionError::UnknownInstruction(format!(
-                "Unknown input {}",
-                s
-            )))
+            Err(InstructionError::UnknownInstruction(s.to_string()))
         }
     }
 }
 
-fn run_instruction(state: &mut Vec<isize>, instruction: &Instruction) {
+pub fn run_instruction(state: &mut Vec<isize>, instruction: &Instruction) {
     let current_value: isize = *state.last().unwrap_or(&(-1));
     match instruction {
         Instruction::Noop => state.push(current_value),
@@ -50,107 +58,104 @@ fn run_instruction(state: &mut Vec<isize>, instruction: &Instruction) {
     }
 }
 
-fn signal_strength(state: &Vec<isize>, index: usize) -> isize {
+fn signal_strength(state: &[isize], index: usize) -> isize {
     let value = *state.get(index - 1).unwrap_or(&1);
     index as isize * value
 }
 
-fn calculate_signal_strength(state: &Vec<isize>, cycles: &Vec<usize>) -> isize {
+fn calculate_signal_strength(state: &[isize], cycles: &[usize]) -> isize {
     cycles.iter().map(|i| signal_strength(state, *i)).sum()
 }
 
-fn draw_screen(state: &Vec<isize>) -> String{
+pub fn draw_screen(state: &[isize]) -> String {
     let mut output = String::with_capacity(40 * 6 + 5);
     for index in 1..241 {
-
         if index != 1 && index % 40 == 1 {
-            output.push_str("\n");
+            output.push('\n');
         }
         if let Some(register) = state.get(index - 1) {
-            let sprite_position = register + 1 % 40;
-            let column: isize = (index % 40) as isize;
-            if (sprite_position - column).abs() <= 1 {
-                output.push_str("#");
+            // Cycle `index` draws pixel `index - 1`, so the last pixel of a
+            // row is column 39, not 0.
+            let column = ((index - 1) % 40) as isize;
+            if (register - column).abs() <= 1 {
+                output.push('#');
             } else {
-                output.push_str(".");
+                output.push('.');
             }
         } else {
-            output.push_str(".");
+            output.push('.');
         }
     }
     output
-
 }
 
-#[cfg(test)]
-pub mod tests {
-    use super::*;
+pub struct DayTen;
 
-    #[test]
-    pub fn test_example_case() {
+impl Solution for DayTen {
+    type Input = Vec<isize>;
+    type Answer = String;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
         let mut states = vec![1];
-        run_instruction(&mut states, &Instruction::AddX(15));
-        run_instruction(&mut states, &Instruction::AddX(-11));
-        run_instruction(&mut states, &Instruction::AddX(6));
-        run_instruction(&mut states, &Instruction::AddX(-3));
-        run_instruction(&mut states, &Instruction::AddX(5));
-        run_instruction(&mut states, &Instruction::AddX(-1));
-        run_instruction(&mut states, &Instruction::AddX(-8));
-        run_instruction(&mut states, &Instruction::AddX(13));
-        run_instruction(&mut states, &Instruction::AddX(4));
-        run_instruction(&mut states, &Instruction::Noop);
-        assert_eq!(420, calculate_signal_strength(&states, &vec![20]));
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            let instruction = Instruction::from_str(line.as_str())
+                .map_err(|err| Error::parse(index + 1, &line, err))?;
+            run_instruction(&mut states, &instruction);
+        }
+        Ok(states)
     }
 
-    #[test]
-    pub fn test_crt() {
-        let mut states = vec![1];
-        run_instruction(&mut states, &Instruction::AddX(15));
-        run_instruction(&mut states, &Instruction::AddX(-11));
-        run_instruction(&mut states, &Instruction::AddX(6));
-        run_instruction(&mut states, &Instruction::AddX(-3));
-        run_instruction(&mut states, &Instruction::AddX(5));
-        run_instruction(&mut states, &Instruction::AddX(-1));
-        run_instruction(&mut states, &Instruction::AddX(-8));
-        run_instruction(&mut states, &Instruction::AddX(13));
-        run_instruction(&mut states, &Instruction::AddX(4));
-        run_instruction(&mut states, &Instruction::Noop);
-        run_instruction(&mut states, &Instruction::AddX(-1));
-        run_instruction(&mut states, &Instruction::AddX(5));
-        run_instruction(&mut states, &Instruction::AddX(-1));
-        run_instruction(&mut states, &Instruction::AddX(5));
-        run_instruction(&mut states, &Instruction::AddX(-1));
-        run_instruction(&mut states, &Instruction::AddX(5));
-        run_instruction(&mut states, &Instruction::AddX(-1));
-        run_instruction(&mut states, &Instruction::AddX(5));
-        run_instruction(&mut states, &Instruction::AddX(-1));
-        run_instruction(&mut states, &Instruction::AddX(-35));
-        run_instruction(&mut states, &Instruction::AddX(1));
-        run_instruction(&mut states, &Instruction::AddX(24));
-        run_instruction(&mut states, &Instruction::AddX(-19));
-        run_instruction(&mut states, &Instruction::AddX(1));
-        run_instruction(&mut states, &Instruction::AddX(16));
-        run_instruction(&mut states, &Instruction::AddX(-11));
-        assert_eq!("##..##..##..##..##..##..##..##..##..##..", draw_screen(&states).lines().next().unwrap());
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(calculate_signal_strength(input, &[20, 60, 100, 140, 180, 220]).to_string())
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(draw_screen(input))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../fixtures/10.txt"),
+            part_one: Some("13140"),
+            part_two: Some(concat!(
+                "##..##..##..##..##..##..##..##..##..##..\n",
+                "###...###...###...###...###...###...###.\n",
+                "####....####....####....####....####....\n",
+                "#####.....#####.....#####.....#####.....\n",
+                "######......######......######......####\n",
+                "#######.......#######.......#######....."
+            )),
+        }]
     }
 }
 
-pub fn run(path: &PathBuf, bonus: bool) -> String {
-    let mut states = vec![1];
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::solution::{example_input, test_examples};
 
-    if let Ok(lines) = read_lines(path) {
-        for maybe_line in lines {
-            if let Ok(line) = maybe_line {
-                if let Ok(instruction) = Instruction::from_str(line.as_str()) {
-                    run_instruction(&mut states, &instruction);
-                }
-            }
-        }
+    test_examples!(DayTen);
+
+    #[test]
+    pub fn test_example_case() {
+        let states = example_input!(DayTen);
+        assert_eq!(420, calculate_signal_strength(&states, &[20]));
+        assert_eq!(
+            "##..##..##..##..##..##..##..##..##..##..",
+            draw_screen(&states).lines().next().unwrap()
+        );
     }
 
-    if bonus {
-        draw_screen(&states)
-    } else {
-        calculate_signal_strength(&states, &vec![20, 60, 100, 140, 180, 220]).to_string()
+    #[test]
+    pub fn test_crt_last_column() {
+        // Cycle 40 draws column 39 of the first row. Taking the column as
+        // `index % 40` put it at column 0 instead, lighting it for a sprite
+        // at the start of the row and leaving it dark for one at the end.
+        let mut states = vec![1; 240];
+        states[39] = 39;
+        assert_eq!(Some('#'), draw_screen(&states).chars().nth(39));
+        states[39] = 0;
+        assert_eq!(Some('.'), draw_screen(&states).chars().nth(39));
     }
 }
@@ -1,76 +1,330 @@
-use std::{collections::HashSet, iter::FromIterator, path::PathBuf};
-use itertools::Itertools;
+use std::{fmt::Display, io::BufRead};
 
-fn get_index(character: &char) -> u32 {
-    let point = *character as u32;
-    if character.is_uppercase() {
-        point - 38
-    } else {
-        point - 96
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
+
+/// The priority of an item, `None` for anything but an ASCII letter.
+pub fn get_index(character: &char) -> Option<u32> {
+    match character {
+        'a'..='z' => Some(*character as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Some(*character as u32 - 'A' as u32 + 27),
+        _ => None,
     }
 }
 
-fn split(line: &str) -> (&str, &str) {
+pub fn split(line: &str) -> (&str, &str) {
     line.split_at(line.len() / 2)
 }
 
-fn overlap<'a>(first: &'a str, second: &'a str) -> HashSet<char> {
-    let first_chars: HashSet<char> = HashSet::from_iter(first.chars());
-    let second_chars: HashSet<char> = HashSet::from_iter(second.chars());
-    first_chars.intersection(&second_chars).copied().collect()
+/// A set of items stored as one bit per priority, 1 to 52.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const EMPTY: ItemSet = ItemSet(0);
+    pub const ALL: ItemSet = ItemSet(((1 << 52) - 1) << 1);
+
+    pub fn from_items(items: &str) -> Result<ItemSet, String> {
+        let mut set = ItemSet::EMPTY;
+        for item in items.chars() {
+            let priority = get_index(&item).ok_or_else(|| format!("'{}' is not an item", item))?;
+            set.0 |= 1 << priority;
+        }
+        Ok(set)
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn contains(self, item: char) -> bool {
+        get_index(&item).is_some_and(|priority| self.0 & (1 << priority) != 0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn priorities(self) -> impl Iterator<Item = u32> {
+        (1..=52).filter(move |priority| self.0 & (1 << priority) != 0)
+    }
+
+    pub fn priority_sum(self) -> u32 {
+        self.priorities().sum()
+    }
+
+    pub fn items(self) -> impl Iterator<Item = char> {
+        self.priorities().map(|priority| match priority {
+            1..=26 => (b'a' + priority as u8 - 1) as char,
+            _ => (b'A' + priority as u8 - 27) as char,
+        })
+    }
+}
+
+impl Display for ItemSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for item in self.items() {
+            write!(f, "{}", item)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn overlap(first: &str, second: &str) -> Result<ItemSet, String> {
+    Ok(ItemSet::from_items(first)?.intersection(ItemSet::from_items(second)?))
 }
 
-fn find_badges(sacks: &Vec<&str>) -> HashSet<char> {
-    let mut result: HashSet<char> = sacks
-        .first()
-        .expect("Cannot have no input")
+pub fn find_badges(sacks: &[&str]) -> Result<ItemSet, String> {
+    let mut result = ItemSet::ALL;
+    for sack in sacks {
+        result = result.intersection(ItemSet::from_items(sack)?);
+    }
+    Ok(result)
+}
+
+/// Something wrong with a rucksack or a group of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    InvalidItem { column: usize, item: char },
+    OddLength(usize),
+    NoSharedItem,
+    SharedItems(ItemSet),
+    NoBadge,
+    Badges(ItemSet),
+    IncompleteGroup { found: usize, expected: usize },
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::InvalidItem { column, item } => {
+                write!(f, "{:?} at column {} is not an item", item, column)
+            }
+            Problem::OddLength(length) => {
+                write!(f, "{} items cannot fill two compartments", length)
+            }
+            Problem::NoSharedItem => write!(f, "compartments share no item"),
+            Problem::SharedItems(items) => {
+                write!(f, "compartments share {} items '{}'", items.len(), items)
+            }
+            Problem::NoBadge => write!(f, "group has no badge"),
+            Problem::Badges(items) => write!(f, "group has {} badges '{}'", items.len(), items),
+            Problem::IncompleteGroup { found, expected } => {
+                write!(f, "last group has {} of {} rucksacks", found, expected)
+            }
+        }
+    }
+}
+
+/// A problem found on a line, or on a range of lines for groups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub first_line: usize,
+    pub last_line: usize,
+    pub problem: Problem,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.first_line == self.last_line {
+            write!(f, "line {}: {}", self.first_line, self.problem)
+        } else {
+            write!(
+                f,
+                "lines {}-{}: {}",
+                self.first_line, self.last_line, self.problem
+            )
+        }
+    }
+}
+
+/// Everything wrong with a single rucksack. Compartments are only compared
+/// once every item is valid and there is an even number of them.
+pub fn rucksack_problems(sack: &str) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = sack
         .chars()
+        .enumerate()
+        .filter(|(_, item)| get_index(item).is_none())
+        .map(|(index, item)| Problem::InvalidItem {
+            column: index + 1,
+            item,
+        })
         .collect();
-    for sack in sacks {
-        result = result
-            .intersection(&sack.chars().collect())
-            .copied()
-            .collect()
+    if !problems.is_empty() {
+        return problems;
+    }
+    if !sack.len().is_multiple_of(2) {
+        problems.push(Problem::OddLength(sack.len()));
+        return problems;
     }
-    result
+    let (first, second) = split(sack);
+    let shared = overlap(first, second).expect("items were checked");
+    match shared.len() {
+        0 => problems.push(Problem::NoSharedItem),
+        1 => {}
+        _ => problems.push(Problem::SharedItems(shared)),
+    }
+    problems
 }
 
-fn process(input: &str) -> u32 {
-    let mut score = 0;
-    let (first_half, second_half) = split(input);
-    let repeats = overlap(&first_half, &second_half);
-    for character in repeats {
-        score += get_index(&character);
+/// The priority of the one item both compartments hold.
+pub fn process(sack: &str) -> Result<u32, Problem> {
+    match rucksack_problems(sack).into_iter().next() {
+        Some(problem) => Err(problem),
+        None => {
+            let (first, second) = split(sack);
+            Ok(overlap(first, second)
+                .expect("items were checked")
+                .priority_sum())
+        }
     }
-    score
 }
 
-pub fn run(path: &PathBuf, bonus: bool) -> u32 {
-    let mut score = 0;
-    if let Ok(lines) = crate::utils::read_lines(path) {
-        if bonus {
-            for chunk in lines.into_iter().tuples::<(_,_,_)>() {
-                if let (Ok(one), Ok(two), Ok(three)) = chunk {
-                    let lines: Vec<&str> = [one.as_str(), two.as_str(), three.as_str()].to_vec();
-                    for badge in find_badges(&lines) {
-                        score += get_index(&badge);
-                    }
-                }
-            }
+/// The one badge every rucksack of a group holds.
+pub fn badge(group: &[&str]) -> Result<ItemSet, Problem> {
+    let badges = find_badges(group).map_err(|_| {
+        let (column, item) = group
+            .iter()
+            .flat_map(|sack| sack.chars().enumerate())
+            .find(|(_, item)| get_index(item).is_none())
+            .expect("find_badges only fails on invalid items");
+        Problem::InvalidItem {
+            column: column + 1,
+            item,
+        }
+    })?;
+    match badges.len() {
+        0 => Err(Problem::NoBadge),
+        1 => Ok(badges),
+        _ => Err(Problem::Badges(badges)),
+    }
+}
+
+/// Checks every rucksack and every group of `group_size`, returning the
+/// problems in line order.
+pub fn diagnose(sacks: &[String], group_size: usize) -> Result<Vec<Finding>> {
+    if group_size == 0 {
+        return Err(Error::semantic(
+            "rucksacks cannot be split into groups of 0",
+        ));
+    }
+    let mut findings = Vec::new();
+    for (index, sack) in sacks.iter().enumerate() {
+        for problem in rucksack_problems(sack) {
+            findings.push(Finding {
+                first_line: index + 1,
+                last_line: index + 1,
+                problem,
+            });
+        }
+    }
+    for (index, group) in sacks.chunks(group_size).enumerate() {
+        let first_line = index * group_size + 1;
+        let last_line = first_line + group.len() - 1;
+        let problem = if group.len() < group_size {
+            Some(Problem::IncompleteGroup {
+                found: group.len(),
+                expected: group_size,
+            })
         } else {
-            for maybe_line in lines {
-                if let Ok(line) = maybe_line {
-                    score += process(&line);
-                }
+            let group: Vec<&str> = group.iter().map(String::as_str).collect();
+            match badge(&group) {
+                // Invalid items are already reported per line.
+                Ok(_) | Err(Problem::InvalidItem { .. }) => None,
+                Err(problem) => Some(problem),
             }
+        };
+        if let Some(problem) = problem {
+            findings.push(Finding {
+                first_line,
+                last_line,
+                problem,
+            });
+        }
+    }
+    findings.sort_by_key(|finding| (finding.first_line, finding.last_line));
+    Ok(findings)
+}
+
+/// Sums the priorities of the items shared by each group of `group_size`
+/// consecutive rucksacks.
+pub fn badge_sum(sacks: &[String], group_size: usize) -> Result<u32> {
+    if group_size == 0 || !sacks.len().is_multiple_of(group_size) {
+        return Err(Error::semantic(format!(
+            "{} rucksacks cannot be split into groups of {}",
+            sacks.len(),
+            group_size
+        )));
+    }
+    let mut score = 0;
+    for (index, group) in sacks.chunks(group_size).enumerate() {
+        let group: Vec<&str> = group.iter().map(String::as_str).collect();
+        let badge = badge(&group).map_err(|problem| {
+            Error::semantic(format!(
+                "lines {}-{}: {}",
+                index * group_size + 1,
+                (index + 1) * group_size,
+                problem
+            ))
+        })?;
+        score += badge.priority_sum();
+    }
+    Ok(score)
+}
+
+pub const GROUP_SIZE: usize = 3;
+
+pub struct DayThree;
+
+impl Solution for DayThree {
+    type Input = Vec<String>;
+    type Answer = u32;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
+        let mut sacks = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            ItemSet::from_items(&line).map_err(|err| Error::parse(index + 1, &line, err))?;
+            sacks.push(line);
+        }
+        Ok(sacks)
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        let mut score = 0;
+        for (index, line) in input.iter().enumerate() {
+            score += process(line).map_err(|err| Error::parse(index + 1, line, err))?;
         }
+        Ok(score)
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        badge_sum(input, GROUP_SIZE)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../fixtures/3.txt"),
+            part_one: Some("157"),
+            part_two: Some("70"),
+        }]
     }
-    score
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::{example_input, test_examples, Day, Part};
+
+    test_examples!(DayThree);
 
     #[test]
     fn test_index() {
@@ -78,10 +332,12 @@ mod tests {
         let lower_z = 'z';
         let upper_a = 'A';
         let upper_z = 'Z';
-        assert_eq!(1, get_index(&lower_a));
-        assert_eq!(26, get_index(&lower_z));
-        assert_eq!(27, get_index(&upper_a));
-        assert_eq!(52, get_index(&upper_z))
+        assert_eq!(Some(1), get_index(&lower_a));
+        assert_eq!(Some(26), get_index(&lower_z));
+        assert_eq!(Some(27), get_index(&upper_a));
+        assert_eq!(Some(52), get_index(&upper_z));
+        assert_eq!(None, get_index(&'['));
+        assert_eq!(None, get_index(&'é'))
     }
 
     #[test]
@@ -91,16 +347,29 @@ mod tests {
 
     #[test]
     fn test_overlap() {
-        assert_eq!(HashSet::from(['a']), overlap("a", "a"));
+        assert_eq!(ItemSet::from_items("a"), overlap("a", "a"));
         assert_eq!(
-            HashSet::from(['p']),
+            ItemSet::from_items("p"),
             overlap("vJrwpWtwJgWr", "hcsFMMfFFhFp")
         );
     }
 
     #[test]
     fn test_process() {
-        assert_eq!(16, process("vJrwpWtwJgWrhcsFMMfFFhFp"));
+        assert_eq!(Ok(16), process("vJrwpWtwJgWrhcsFMMfFFhFp"));
+        assert_eq!(Err(Problem::OddLength(3)), process("aba"));
+        assert_eq!(Err(Problem::NoSharedItem), process("abcd"));
+        assert_eq!(
+            Err(Problem::SharedItems(ItemSet::from_items("ab").unwrap())),
+            process("abab")
+        );
+        assert_eq!(
+            Err(Problem::InvalidItem {
+                column: 2,
+                item: '1'
+            }),
+            process("a1a1")
+        );
     }
 
     #[test]
@@ -115,7 +384,75 @@ mod tests {
             "ttgJtRGJQctTZtZT",
             "CrZsJsPPZsGzwwsLwLmpwMDw",
         ];
-        assert_eq!(HashSet::from(['r']), find_badges(&input_one));
-        assert_eq!(HashSet::from(['Z']), find_badges(&input_two));
+        assert_eq!(ItemSet::from_items("r"), find_badges(&input_one));
+        assert_eq!(ItemSet::from_items("Z"), find_badges(&input_two));
+    }
+
+    #[test]
+    fn test_item_set() {
+        let set = ItemSet::from_items("aabZ").unwrap();
+        assert_eq!(3, set.len());
+        assert!(set.contains('Z') && !set.contains('z') && !set.contains('1'));
+        assert_eq!(1 + 2 + 52, set.priority_sum());
+        let other = ItemSet::from_items("bc").unwrap();
+        assert_eq!(ItemSet::from_items("b").unwrap(), set.intersection(other));
+        assert_eq!(ItemSet::from_items("abcZ").unwrap(), set.union(other));
+        assert_eq!(52, ItemSet::ALL.len());
+        assert!(ItemSet::EMPTY.is_empty());
+        assert!(ItemSet::from_items("ab c").is_err());
+    }
+
+    #[test]
+    fn test_group_sizes() {
+        let sacks: Vec<String> = ["ab", "bc", "cd", "ca"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(2 + 3, badge_sum(&sacks, 2).unwrap());
+        assert!(badge_sum(&sacks, 4).is_err());
+        assert!(badge_sum(&sacks, 1).is_err());
+        let sacks: Vec<String> = ["ab", "ac", "ad", "ae"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(1, badge_sum(&sacks, 4).unwrap());
+        assert!(badge_sum(&sacks, 3).is_err());
+        assert!(badge_sum(&sacks, 0).is_err());
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let sacks: Vec<String> = ["aBa", "ab?b", "abcabc", "axya", "ayxa", "xyab", "q"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let findings: Vec<String> = diagnose(&sacks, 3)
+            .unwrap()
+            .iter()
+            .map(Finding::to_string)
+            .collect();
+        assert_eq!(
+            vec![
+                "line 1: 3 items cannot fill two compartments",
+                "line 2: '?' at column 3 is not an item",
+                "line 3: compartments share 3 items 'abc'",
+                "lines 4-6: group has 3 badges 'axy'",
+                "line 6: compartments share no item",
+                "line 7: 1 items cannot fill two compartments",
+                "line 7: last group has 1 of 3 rucksacks",
+            ],
+            findings
+        );
+        let example = example_input!(DayThree);
+        assert!(diagnose(&example, 3).unwrap().is_empty());
+        // A group size of 0 used to report every line as line 1.
+        assert!(diagnose(&sacks, 0).is_err());
+    }
+
+    #[test]
+    fn test_invalid_rucksacks_fail() {
+        assert!(DayThree.run_str("abab\n", Part::One).is_err());
+        assert!(DayThree.run_str("a[a[\n", Part::One).is_err());
+        assert!(DayThree.run_str("aa\nbb\naa\n", Part::Two).is_err());
     }
 }
@@ -1,115 +1,117 @@
-use std::path::PathBuf;
+use std::io::BufRead;
 
-trait Scorable {
-    fn get_score(&self) -> u32;
-}
+use crate::error::{Error, Result};
+use crate::solution::{Example, Solution};
 
-#[derive(PartialEq, Debug)]
-enum Hand {
-    Rock,
-    Paper,
-    Scissors,
-}
+pub mod analysis;
+pub mod rules;
+
+use rules::{play, Decoder, Variant};
 
-impl Scorable for Hand {
-    fn get_score(&self) -> u32 {
-        match self {
-            Hand::Rock => 1,
-            Hand::Paper => 2,
-            Hand::Scissors => 3,
+pub struct DayTwo;
+
+/// Splits each line into its (opponent, strategy) symbols without deciding
+/// what they mean, so rounds can be read for any set of rules. Blank lines
+/// are skipped.
+pub fn parse_rounds(input: &mut dyn BufRead) -> Result<Vec<(String, String)>> {
+    let mut rounds = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        if let Some(round) = split_round(index + 1, &line?)? {
+            rounds.push(round);
         }
     }
+    Ok(rounds)
 }
 
-impl From<String> for Hand {
-    fn from(str: String) -> Self {
-        match str.as_str() {
-            "X" => Hand::Rock,
-            "A" => Hand::Rock,
-            "Y" => Hand::Paper,
-            "B" => Hand::Paper,
-            "Z" => Hand::Scissors,
-            "C" => Hand::Scissors,
-            _ => panic!("Unexpected string"),
-        }
+fn split_round(number: usize, line: &str) -> Result<Option<(String, String)>> {
+    if line.trim().is_empty() {
+        return Ok(None);
     }
+    let (theirs, ours) = line
+        .split_once(' ')
+        .ok_or_else(|| Error::parse(number, line, "expected 2 hands per line"))?;
+    Ok(Some((theirs.to_string(), ours.to_string())))
 }
 
-#[derive(Debug)]
-enum Outcome {
-    Win,
-    Loss,
-    Draw,
-}
+impl Solution for DayTwo {
+    /// Each round's (opponent, strategy) symbols, decoded when scoring.
+    type Input = Vec<(String, String)>;
+    type Answer = u32;
 
-impl Scorable for Outcome {
-    fn get_score(&self) -> u32 {
-        match self {
-            Outcome::Win => 6,
-            Outcome::Loss => 0,
-            Outcome::Draw => 3,
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input> {
+        let variant = Variant::standard();
+        let mut rounds = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            let (theirs, ours) = match split_round(index + 1, &line)? {
+                Some(round) => round,
+                None => continue,
+            };
+            let their_hand = variant
+                .rules
+                .opponent(&theirs)
+                .map_err(|err| Error::parse(index + 1, &line, err))?;
+            // The strategy column must make sense under either interpretation.
+            for decoder in [&variant.hands as &dyn Decoder, &variant.outcomes] {
+                decoder
+                    .decode(&variant.rules, their_hand, &ours)
+                    .map_err(|err| Error::parse(index + 1, &line, err))?;
+            }
+            rounds.push((theirs, ours));
         }
+        Ok(rounds)
     }
-}
 
-impl From<String> for Outcome {
-    fn from(str: String) -> Self {
-        match str.as_str() {
-            "X" => Outcome::Loss,
-            "Y" => Outcome::Draw,
-            "Z" => Outcome::Win,
-            _ => panic!("Unexpected input"),
-        }
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        let variant = Variant::standard();
+        play(&variant.rules, &variant.hands, input)
     }
-}
 
-fn calculate_hand(outcome: &Outcome, their_hand: &Hand) -> Hand {
-    match (outcome, their_hand) {
-        (Outcome::Win, Hand::Rock) => Hand::Paper,
-        (Outcome::Win, Hand::Paper) => Hand::Scissors,
-        (Outcome::Win, Hand::Scissors) => Hand::Rock,
-        (Outcome::Loss, Hand::Rock) => Hand::Scissors,
-        (Outcome::Loss, Hand::Paper) => Hand::Rock,
-        (Outcome::Loss, Hand::Scissors) => Hand::Paper,
-        (Outcome::Draw, Hand::Rock) => Hand::Rock,
-        (Outcome::Draw, Hand::Paper) => Hand::Paper,
-        (Outcome::Draw, Hand::Scissors) => Hand::Scissors,
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        let variant = Variant::standard();
+        play(&variant.rules, &variant.outcomes, input)
     }
-}
 
-fn outcome(our_hand: &Hand, their_hand: &Hand) -> Outcome {
-    match (our_hand, their_hand) {
-        (Hand::Rock, Hand::Paper) => Outcome::Loss,
-        (Hand::Rock, Hand::Scissors) => Outcome::Win,
-        (Hand::Paper, Hand::Rock) => Outcome::Win,
-        (Hand::Paper, Hand::Scissors) => Outcome::Loss,
-        (Hand::Scissors, Hand::Rock) => Outcome::Loss,
-        (Hand::Scissors, Hand::Paper) => Outcome::Win,
-        _ => Outcome::Draw,
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../fixtures/2.txt"),
+            part_one: Some("15"),
+            part_two: Some("12"),
+        }]
     }
 }
 
-pub fn run(path: &PathBuf, bonus: bool) -> u32 {
-    let mut score = 0;
-    if let Ok(lines) = crate::utils::read_lines(path) {
-        for maybe_line in lines {
-            if let Ok(line) = maybe_line {
-                let hands: Vec<&str> = line.split(" ").collect();
-                assert!(hands.len() == 2, "Expected only 2 hands per line");
-                let their_hand: Hand = hands.first().unwrap().to_string().into();
-                if bonus {
-                    let outcome: Outcome = hands.last().unwrap().to_string().into();
-                    let our_hand = calculate_hand(&outcome, &their_hand);
-                    score += our_hand.get_score();
-                    score += outcome.get_score();
-                } else {
-                    let our_hand: Hand = hands.last().unwrap().to_string().into();
-                    let outcome = outcome(&our_hand, &their_hand);
-                    score += our_hand.get_score();
-                    score += outcome.get_score();
-                }
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::test_examples;
+
+    test_examples!(DayTwo);
+
+    #[test]
+    fn test_invalid_round() {
+        assert!(DayTwo.parse(&mut "A W".as_bytes()).is_err());
+        assert!(DayTwo.parse(&mut "D X".as_bytes()).is_err());
+        assert!(DayTwo.parse(&mut "AX".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_rounds() {
+        // Symbols outside the standard game are left for the rules to judge.
+        assert_eq!(
+            vec![
+                ("A".to_string(), "Y".to_string()),
+                ("D".to_string(), "W".to_string())
+            ],
+            parse_rounds(&mut "A Y\nD W".as_bytes()).unwrap()
+        );
+        assert!(parse_rounds(&mut "A Y\nAX".as_bytes()).is_err());
+        // A trailing blank line is not a round.
+        assert_eq!(
+            2,
+            parse_rounds(&mut "A Y\nB X\n\n".as_bytes()).unwrap().len()
+        );
+        let err = DayTwo.parse(&mut "A Y\n\nB W".as_bytes()).unwrap_err();
+        assert!(err.to_string().starts_with("line 3:"), "{}", err);
     }
-    score
 }
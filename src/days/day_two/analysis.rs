@@ -0,0 +1,298 @@
+use std::{cmp::Reverse, collections::BTreeMap, fmt::Display};
+
+use itertools::Itertools;
+
+use crate::error::{Error, Result};
+
+use super::rules::{Outcome, Rules};
+
+/// How the strategy column is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    Hands,
+    Outcomes,
+}
+
+impl Display for Reading {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reading::Hands => write!(f, "hands"),
+            Reading::Outcomes => write!(f, "outcomes"),
+        }
+    }
+}
+
+const OUTCOMES: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
+/// Most decodings `analyse` will score, every one of them being kept. Seven
+/// symbols over seven moves are 5,040, nine over nine are too many.
+pub const MAX_DECODINGS: usize = 100_000;
+
+/// One way of decoding the strategy column and the score it gives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scored {
+    /// Each strategy symbol with the move or outcome it decodes to.
+    pub mapping: Vec<(String, String)>,
+    pub score: u32,
+}
+
+impl Display for Scored {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mapping = self
+            .mapping
+            .iter()
+            .map(|(symbol, target)| format!("{}={}", symbol, target))
+            .join(" ");
+        write!(f, "{}: {}", self.score, mapping)
+    }
+}
+
+/// The range a single round's score covers across every decoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Swing {
+    /// 1-based, like line numbers.
+    pub round: usize,
+    pub worst: u32,
+    pub best: u32,
+}
+
+impl Swing {
+    pub fn swing(&self) -> u32 {
+        self.best - self.worst
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub reading: Reading,
+    /// Every decoding, best score first.
+    pub scored: Vec<Scored>,
+    /// Every round, largest swing first.
+    pub swings: Vec<Swing>,
+}
+
+impl Analysis {
+    pub fn best(&self) -> &Scored {
+        self.scored.first().expect("there is always a decoding")
+    }
+
+    pub fn worst(&self) -> &Scored {
+        self.scored.last().expect("there is always a decoding")
+    }
+
+    /// How many decodings reach each score.
+    pub fn distribution(&self) -> BTreeMap<u32, usize> {
+        self.scored
+            .iter()
+            .map(|scored| scored.score)
+            .counts()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Scores the guide under every one-to-one mapping of its strategy symbols to
+/// moves or outcomes. With as many symbols as targets, like X, Y and Z for
+/// rock, paper, scissors, these are exactly the bijections.
+///
+/// The guide is only read once, into what each symbol scores for each target,
+/// so a decoding costs one lookup per symbol rather than a pass over the
+/// rounds.
+pub fn analyse(rules: &Rules, rounds: &[(String, String)], reading: Reading) -> Result<Analysis> {
+    let symbols: Vec<&String> = rounds
+        .iter()
+        .map(|(_, ours)| ours)
+        .unique()
+        .sorted()
+        .collect();
+    let targets = match reading {
+        Reading::Hands => rules.len(),
+        Reading::Outcomes => OUTCOMES.len(),
+    };
+    if symbols.len() > targets {
+        return Err(Error::semantic(format!(
+            "{} strategy symbols cannot each decode to one of {} {}",
+            symbols.len(),
+            targets,
+            reading
+        )));
+    }
+    let decodings = (targets - symbols.len() + 1..=targets)
+        .try_fold(1usize, |count, factor| count.checked_mul(factor))
+        .filter(|count| *count <= MAX_DECODINGS)
+        .ok_or_else(|| {
+            Error::semantic(format!(
+                "{} strategy symbols over {} {} have more than {} decodings to score",
+                symbols.len(),
+                targets,
+                reading,
+                MAX_DECODINGS
+            ))
+        })?;
+    let name = |target: usize| match reading {
+        Reading::Hands => rules.name(target).to_string(),
+        Reading::Outcomes => OUTCOMES[target].to_string(),
+    };
+    // What a round scores against `theirs` when its symbol decodes to `target`.
+    let round_score = |theirs: usize, target: usize| {
+        let ours = match reading {
+            Reading::Hands => target,
+            Reading::Outcomes => rules.response(theirs, OUTCOMES[target]),
+        };
+        rules.score(ours, theirs)
+    };
+
+    let mut opponents = Vec::with_capacity(rounds.len());
+    let mut counts = vec![vec![0u32; rules.len()]; symbols.len()];
+    for (index, (theirs, ours)) in rounds.iter().enumerate() {
+        let theirs = rules
+            .opponent(theirs)
+            .map_err(|err| Error::semantic(format!("round {}: {}", index + 1, err)))?;
+        let symbol = symbols
+            .binary_search(&ours)
+            .expect("every symbol is listed");
+        counts[symbol][theirs] += 1;
+        opponents.push(theirs);
+    }
+    // gains[symbol][target] is the total of every round with that symbol.
+    let gains: Vec<Vec<u32>> = counts
+        .iter()
+        .map(|counts| {
+            (0..targets)
+                .map(|target| {
+                    counts
+                        .iter()
+                        .enumerate()
+                        .map(|(theirs, count)| count * round_score(theirs, target))
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut scored = Vec::with_capacity(decodings);
+    for choice in (0..targets).permutations(symbols.len()) {
+        let score = choice
+            .iter()
+            .enumerate()
+            .map(|(symbol, target)| gains[symbol][*target])
+            .sum();
+        let mapping = symbols
+            .iter()
+            .zip(&choice)
+            .map(|(symbol, target)| (symbol.to_string(), name(*target)))
+            .collect();
+        scored.push(Scored { mapping, score });
+    }
+    scored.sort_by_key(|scored| Reverse(scored.score));
+
+    // A symbol can decode to any target, so a round's range only depends on
+    // its own opponent.
+    let mut swings: Vec<Swing> = opponents
+        .iter()
+        .enumerate()
+        .map(|(index, theirs)| {
+            let scores = (0..targets).map(|target| round_score(*theirs, target));
+            Swing {
+                round: index + 1,
+                worst: scores.clone().min().expect("at least 3 targets"),
+                best: scores.max().expect("at least 3 targets"),
+            }
+        })
+        .collect();
+    swings.sort_by(|a, b| b.swing().cmp(&a.swing()).then(a.round.cmp(&b.round)));
+
+    Ok(Analysis {
+        reading,
+        scored,
+        swings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::day_two::rules::{play, OutcomeDecoder, Variant};
+    use crate::days::day_two::DayTwo;
+    use crate::solution::example_input;
+
+    #[test]
+    fn test_analyse_hands() {
+        let guide = example_input!(DayTwo);
+        let analysis = analyse(&Rules::standard(), &guide, Reading::Hands).unwrap();
+        assert_eq!(6, analysis.scored.len());
+        assert_eq!(6, analysis.distribution().values().sum::<usize>());
+        assert!(analysis
+            .scored
+            .iter()
+            .any(|scored| scored.score == 15
+                && scored.to_string() == "15: X=rock Y=paper Z=scissors"));
+        assert_eq!(24, analysis.best().score);
+        assert_eq!(6, analysis.worst().score);
+        assert!(analysis
+            .swings
+            .windows(2)
+            .all(|w| w[0].swing() >= w[1].swing()));
+    }
+
+    #[test]
+    fn test_analyse_outcomes() {
+        let guide = example_input!(DayTwo);
+        let analysis = analyse(&Rules::standard(), &guide, Reading::Outcomes).unwrap();
+        assert_eq!(6, analysis.scored.len());
+        assert!(analysis.scored.iter().any(|scored| scored.score == 12));
+        // Against paper a loss means rock and a win scissors, 1 to 9.
+        let swings: Vec<(usize, u32, u32)> = analysis
+            .swings
+            .iter()
+            .map(|swing| (swing.round, swing.worst, swing.best))
+            .collect();
+        assert_eq!(vec![(2, 1, 9), (1, 3, 8), (3, 2, 7)], swings);
+    }
+
+    #[test]
+    fn test_too_many_symbols() {
+        let guide = vec![
+            ("A".to_string(), "W".to_string()),
+            ("A".to_string(), "X".to_string()),
+            ("A".to_string(), "Y".to_string()),
+            ("A".to_string(), "Z".to_string()),
+        ];
+        assert!(analyse(&Rules::standard(), &guide, Reading::Outcomes).is_err());
+    }
+
+    #[test]
+    fn test_agrees_with_play() {
+        let guide = example_input!(DayTwo);
+        let variant = Variant::standard();
+        let analysis = analyse(&variant.rules, &guide, Reading::Outcomes).unwrap();
+        for scored in &analysis.scored {
+            let decoder = OutcomeDecoder::new(
+                scored
+                    .mapping
+                    .iter()
+                    .map(|(symbol, outcome)| (symbol.clone(), outcome.parse().unwrap()))
+                    .collect(),
+            );
+            assert_eq!(
+                play(&variant.rules, &decoder, &guide).unwrap(),
+                scored.score
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_many_decodings() {
+        let moves: Vec<String> = (0..9).map(|index| format!("m{}", index)).collect();
+        let opponent = [("A".to_string(), "m0".to_string())].into_iter().collect();
+        let rules = Rules::new(moves, (1..=9).collect(), [6, 3, 0], &opponent).unwrap();
+        let guide: Vec<(String, String)> = (0..9)
+            .map(|symbol| ("A".to_string(), symbol.to_string()))
+            .collect();
+        // 9! decodings are refused rather than enumerated, 9 * 8 * 7 * 6 * 5
+        // for five symbols are still scored.
+        assert!(analyse(&rules, &guide, Reading::Hands).is_err());
+        let analysis = analyse(&rules, &guide[..5], Reading::Hands).unwrap();
+        assert_eq!(15_120, analysis.scored.len());
+    }
+}
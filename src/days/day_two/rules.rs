@@ -0,0 +1,445 @@
+use std::{collections::HashMap, fmt::Display, fs, path::Path, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "win" => Ok(Outcome::Win),
+            "loss" => Ok(Outcome::Loss),
+            "draw" => Ok(Outcome::Draw),
+            _ => Err(format!("unknown outcome '{}'", s)),
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Win => write!(f, "win"),
+            Outcome::Loss => write!(f, "loss"),
+            Outcome::Draw => write!(f, "draw"),
+        }
+    }
+}
+
+/// A cyclic game of an odd number of moves. Moves are listed in cycle order
+/// and each one beats the half of the other moves just before it, so for
+/// rock, paper, scissors paper beats rock and rock beats scissors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    moves: Vec<String>,
+    move_scores: Vec<u32>,
+    win: u32,
+    draw: u32,
+    loss: u32,
+    opponent: HashMap<String, usize>,
+}
+
+impl Rules {
+    /// `move_scores` line up with `moves`, `outcome_scores` are for a win,
+    /// draw and loss, and `opponent` maps the symbols of the first column to
+    /// move names.
+    pub fn new(
+        moves: Vec<String>,
+        move_scores: Vec<u32>,
+        outcome_scores: [u32; 3],
+        opponent: &HashMap<String, String>,
+    ) -> Result<Rules, String> {
+        if moves.len() < 3 || moves.len().is_multiple_of(2) {
+            return Err(format!(
+                "a cyclic game needs an odd number of at least 3 moves, got {}",
+                moves.len()
+            ));
+        }
+        if move_scores.len() != moves.len() {
+            return Err(format!(
+                "expected a score for each of the {} moves, got {}",
+                moves.len(),
+                move_scores.len()
+            ));
+        }
+        let [win, draw, loss] = outcome_scores;
+        let mut rules = Rules {
+            moves,
+            move_scores,
+            win,
+            draw,
+            loss,
+            opponent: HashMap::new(),
+        };
+        if let Some(duplicate) = rules
+            .moves
+            .iter()
+            .enumerate()
+            .find(|(index, name)| rules.moves[..*index].contains(name))
+        {
+            return Err(format!("move '{}' is listed twice", duplicate.1));
+        }
+        rules.opponent = rules.symbols(opponent)?;
+        Ok(rules)
+    }
+
+    /// Rock, paper, scissors as the puzzle scores it, with the opponent
+    /// playing A, B and C.
+    pub fn standard() -> Rules {
+        let opponent = [("A", "rock"), ("B", "paper"), ("C", "scissors")]
+            .iter()
+            .map(|(symbol, name)| (symbol.to_string(), name.to_string()))
+            .collect();
+        Rules::new(
+            vec!["rock".into(), "paper".into(), "scissors".into()],
+            vec![1, 2, 3],
+            [6, 3, 0],
+            &opponent,
+        )
+        .expect("standard rules are valid")
+    }
+
+    // A game always has at least three moves, so there is no is_empty.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn name(&self, index: usize) -> &str {
+        &self.moves[index]
+    }
+
+    pub fn move_index(&self, name: &str) -> Option<usize> {
+        self.moves.iter().position(|candidate| candidate == name)
+    }
+
+    /// Resolves a mapping of symbols to move names into move indices.
+    pub fn symbols(
+        &self,
+        names: &HashMap<String, String>,
+    ) -> Result<HashMap<String, usize>, String> {
+        names
+            .iter()
+            .map(|(symbol, name)| match self.move_index(name) {
+                Some(index) => Ok((symbol.clone(), index)),
+                None => Err(format!("'{}' maps to unknown move '{}'", symbol, name)),
+            })
+            .collect()
+    }
+
+    pub fn opponent(&self, symbol: &str) -> Result<usize, String> {
+        self.opponent
+            .get(symbol)
+            .copied()
+            .ok_or_else(|| format!("unknown opponent move '{}'", symbol))
+    }
+
+    pub fn outcome(&self, ours: usize, theirs: usize) -> Outcome {
+        let distance = (ours + self.len() - theirs) % self.len();
+        if distance == 0 {
+            Outcome::Draw
+        } else if distance <= self.len() / 2 {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        }
+    }
+
+    /// The move giving `outcome` against `theirs`. With more than three moves
+    /// several moves can win or lose, the one next to `theirs` in the cycle is
+    /// picked.
+    pub fn response(&self, theirs: usize, outcome: Outcome) -> usize {
+        match outcome {
+            Outcome::Draw => theirs,
+            Outcome::Win => (theirs + 1) % self.len(),
+            Outcome::Loss => (theirs + self.len() - 1) % self.len(),
+        }
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Win => self.win,
+            Outcome::Draw => self.draw,
+            Outcome::Loss => self.loss,
+        }
+    }
+
+    /// Our score for a single round.
+    pub fn score(&self, ours: usize, theirs: usize) -> u32 {
+        self.move_scores[ours] + self.outcome_score(self.outcome(ours, theirs))
+    }
+}
+
+/// Turns the second column of a strategy guide into the move we play.
+pub trait Decoder {
+    fn decode(&self, rules: &Rules, theirs: usize, symbol: &str) -> Result<usize, String>;
+}
+
+/// Reads the strategy column as the move to play.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandDecoder {
+    symbols: HashMap<String, usize>,
+}
+
+impl HandDecoder {
+    pub fn new(symbols: HashMap<String, usize>) -> HandDecoder {
+        HandDecoder { symbols }
+    }
+}
+
+impl Decoder for HandDecoder {
+    fn decode(&self, _rules: &Rules, _theirs: usize, symbol: &str) -> Result<usize, String> {
+        self.symbols
+            .get(symbol)
+            .copied()
+            .ok_or_else(|| format!("unknown hand '{}'", symbol))
+    }
+}
+
+/// Reads the strategy column as the outcome the round needs to have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutcomeDecoder {
+    symbols: HashMap<String, Outcome>,
+}
+
+impl OutcomeDecoder {
+    pub fn new(symbols: HashMap<String, Outcome>) -> OutcomeDecoder {
+        OutcomeDecoder { symbols }
+    }
+}
+
+impl Decoder for OutcomeDecoder {
+    fn decode(&self, rules: &Rules, theirs: usize, symbol: &str) -> Result<usize, String> {
+        let outcome = self
+            .symbols
+            .get(symbol)
+            .ok_or_else(|| format!("unknown outcome '{}'", symbol))?;
+        Ok(rules.response(theirs, *outcome))
+    }
+}
+
+/// Total score of following the guide, given as (opponent, strategy) symbols.
+pub fn play(rules: &Rules, decoder: &dyn Decoder, rounds: &[(String, String)]) -> Result<u32> {
+    let mut score = 0;
+    for (index, (theirs, ours)) in rounds.iter().enumerate() {
+        let round = |err| Error::semantic(format!("round {}: {}", index + 1, err));
+        let theirs = rules.opponent(theirs).map_err(round)?;
+        let ours = decoder.decode(rules, theirs, ours).map_err(round)?;
+        score += rules.score(ours, theirs);
+    }
+    Ok(score)
+}
+
+/// A rule set together with both readings of the strategy column, as loaded
+/// from a config file:
+///
+/// ```toml
+/// moves = ["rock", "paper", "scissors"]
+///
+/// [move_scores]
+/// rock = 1
+/// paper = 2
+/// scissors = 3
+///
+/// [outcome_scores]
+/// win = 6
+/// draw = 3
+/// loss = 0
+///
+/// [opponent]
+/// A = "rock"
+///
+/// [as_hands]
+/// X = "rock"
+///
+/// [as_outcomes]
+/// X = "loss"
+/// ```
+///
+/// Move scores default to each move's position in the cycle starting at 1,
+/// and outcome scores default to the puzzle's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub rules: Rules,
+    pub hands: HandDecoder,
+    pub outcomes: OutcomeDecoder,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawVariant {
+    moves: Vec<String>,
+    #[serde(default)]
+    move_scores: HashMap<String, u32>,
+    #[serde(default)]
+    outcome_scores: HashMap<String, u32>,
+    opponent: HashMap<String, String>,
+    as_hands: HashMap<String, String>,
+    as_outcomes: HashMap<String, String>,
+}
+
+impl Variant {
+    pub fn standard() -> Variant {
+        let rules = Rules::standard();
+        let hands = [("X", 0), ("Y", 1), ("Z", 2)]
+            .iter()
+            .map(|(symbol, index)| (symbol.to_string(), *index))
+            .collect();
+        let outcomes = [
+            ("X", Outcome::Loss),
+            ("Y", Outcome::Draw),
+            ("Z", Outcome::Win),
+        ]
+        .iter()
+        .map(|(symbol, outcome)| (symbol.to_string(), *outcome))
+        .collect();
+        Variant {
+            rules,
+            hands: HandDecoder::new(hands),
+            outcomes: OutcomeDecoder::new(outcomes),
+        }
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Variant, String> {
+        let raw: RawVariant = toml::from_str(contents).map_err(|err| err.to_string())?;
+        for name in raw.move_scores.keys() {
+            if !raw.moves.contains(name) {
+                return Err(format!("score given for unknown move '{}'", name));
+            }
+        }
+        let move_scores = raw
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                raw.move_scores
+                    .get(name)
+                    .copied()
+                    .unwrap_or(index as u32 + 1)
+            })
+            .collect();
+        let mut outcome_scores = [6, 3, 0];
+        for (name, score) in &raw.outcome_scores {
+            match Outcome::from_str(name)? {
+                Outcome::Win => outcome_scores[0] = *score,
+                Outcome::Draw => outcome_scores[1] = *score,
+                Outcome::Loss => outcome_scores[2] = *score,
+            }
+        }
+        let rules = Rules::new(raw.moves, move_scores, outcome_scores, &raw.opponent)?;
+        let hands = HandDecoder::new(rules.symbols(&raw.as_hands)?);
+        let outcomes = raw
+            .as_outcomes
+            .iter()
+            .map(|(symbol, outcome)| Ok((symbol.clone(), Outcome::from_str(outcome)?)))
+            .collect::<Result<_, String>>()?;
+        Ok(Variant {
+            rules,
+            hands,
+            outcomes: OutcomeDecoder::new(outcomes),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Variant> {
+        Variant::from_toml(&fs::read_to_string(path)?)
+            .map_err(|err| Error::semantic(format!("{}: {}", path.display(), err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rounds(guide: &str) -> Vec<(String, String)> {
+        guide
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(theirs, ours)| (theirs.to_string(), ours.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_standard_rules() {
+        let rules = Rules::standard();
+        assert_eq!(Outcome::Win, rules.outcome(1, 0));
+        assert_eq!(Outcome::Win, rules.outcome(0, 2));
+        assert_eq!(Outcome::Loss, rules.outcome(2, 0));
+        assert_eq!(Outcome::Draw, rules.outcome(1, 1));
+        for theirs in 0..3 {
+            for outcome in [Outcome::Win, Outcome::Draw, Outcome::Loss] {
+                assert_eq!(
+                    outcome,
+                    rules.outcome(rules.response(theirs, outcome), theirs)
+                );
+            }
+        }
+        assert_eq!(8, rules.score(1, 0));
+    }
+
+    #[test]
+    fn test_rpsls() {
+        let variant = Variant::from_toml(include_str!("../../../fixtures/2-rpsls.toml")).unwrap();
+        let rules = &variant.rules;
+        let index = |name| rules.move_index(name).unwrap();
+        assert_eq!(
+            Outcome::Win,
+            rules.outcome(index("spock"), index("scissors"))
+        );
+        assert_eq!(Outcome::Win, rules.outcome(index("lizard"), index("spock")));
+        assert_eq!(Outcome::Loss, rules.outcome(index("rock"), index("paper")));
+        assert_eq!(Outcome::Win, rules.outcome(index("rock"), index("lizard")));
+        for theirs in 0..rules.len() {
+            for outcome in [Outcome::Win, Outcome::Draw, Outcome::Loss] {
+                assert_eq!(
+                    outcome,
+                    rules.outcome(rules.response(theirs, outcome), theirs)
+                );
+            }
+        }
+        // Spock beats rock, rock loses to Spock, scissors beats lizard.
+        let guide = rounds("A Z\nE V\nD X");
+        assert_eq!(11 + 1 + 9, play(rules, &variant.hands, &guide).unwrap());
+        // A draw against rock, then losing to lizard and beating Spock with paper.
+        let guide = rounds("A Y\nD X\nE Z");
+        assert_eq!(4 + 2 + 8, play(rules, &variant.outcomes, &guide).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_variants() {
+        assert!(Variant::from_toml(
+            "moves = [\"a\", \"b\"]\n[opponent]\n[as_hands]\n[as_outcomes]\n"
+        )
+        .is_err());
+        assert!(Variant::from_toml(
+            "moves = [\"a\", \"b\", \"a\"]\n[opponent]\n[as_hands]\n[as_outcomes]\n"
+        )
+        .is_err());
+        assert!(Variant::from_toml(
+            "moves = [\"a\", \"b\", \"c\"]\n[opponent]\nA = \"d\"\n[as_hands]\n[as_outcomes]\n"
+        )
+        .is_err());
+        assert!(Variant::from_toml(
+            "moves = [\"a\", \"b\", \"c\"]\n[opponent]\n[as_hands]\n[as_outcomes]\nX = \"tie\"\n"
+        )
+        .is_err());
+        assert!(Variant::from_toml(
+            "moves = [\"a\", \"b\", \"c\"]\n[opponent]\n[as_hands]\n[as_outcomes]\n"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_unknown_symbol() {
+        let variant = Variant::standard();
+        let err = play(&variant.rules, &variant.hands, &rounds("A X\nA Q")).unwrap_err();
+        assert_eq!("round 2: unknown hand 'Q'", err.to_string());
+    }
+}
@@ -1,10 +1,36 @@
-pub mod day_one;
-pub mod day_two;
-pub mod day_three;
-pub mod day_four;
-pub mod day_five;
-pub mod day_six;
-pub mod day_seven;
+use crate::solution::Day;
+
 pub mod day_eight;
+pub mod day_five;
+pub mod day_four;
 pub mod day_nine;
+pub mod day_one;
+pub mod day_seven;
+pub mod day_six;
 pub mod day_ten;
+pub mod day_three;
+pub mod day_two;
+
+const DAYS: [&dyn Day; 10] = [
+    &day_one::DayOne,
+    &day_two::DayTwo,
+    &day_three::DayThree,
+    &day_four::DayFour,
+    &day_five::DayFive,
+    &day_six::DaySix,
+    &day_seven::DaySeven,
+    &day_eight::DayEight,
+    &day_nine::DayNine,
+    &day_ten::DayTen,
+];
+
+pub fn get(day: usize) -> Option<&'static dyn Day> {
+    if day == 0 {
+        return None;
+    }
+    DAYS.get(day - 1).copied()
+}
+
+pub fn count() -> usize {
+    DAYS.len()
+}
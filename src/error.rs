@@ -0,0 +1,60 @@
+use std::{fmt::Display, io};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse {
+        line: usize,
+        text: String,
+        message: String,
+    },
+    Semantic(String),
+    Fetch(String),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Builds a parse error for the 1-based `line` of the input.
+    pub fn parse(line: usize, text: &str, message: impl Display) -> Error {
+        Error::Parse {
+            line,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn semantic(message: impl Display) -> Error {
+        Error::Semantic(message.to_string())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "could not read input: {}", err),
+            Error::Parse {
+                line,
+                text,
+                message,
+            } => write!(f, "line {}: {} in '{}'", line, message, text),
+            Error::Semantic(message) => write!(f, "{}", message),
+            Error::Fetch(message) => write!(f, "request failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
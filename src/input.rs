@@ -0,0 +1,259 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use reqwest::{blocking::Client, header};
+
+use crate::error::{Error, Result};
+
+pub const YEAR: u16 = 2022;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const SESSION_VAR: &str = "AOC_SESSION";
+const BASE_URL_VAR: &str = "AOC_BASE_URL";
+const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+/// Downloads puzzle inputs and keeps them in a local cache so each day is only
+/// ever fetched once.
+pub struct InputProvider {
+    base_url: String,
+    cache_dir: PathBuf,
+    session: Option<String>,
+}
+
+impl InputProvider {
+    pub fn new(base_url: &str, cache_dir: PathBuf, session: Option<String>) -> InputProvider {
+        InputProvider {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            cache_dir,
+            session,
+        }
+    }
+
+    /// Configures the provider from `AOC_BASE_URL`, `AOC_CACHE_DIR` and
+    /// `AOC_SESSION`, falling back to `~/.config/advent-2022/session` for the
+    /// token and `~/.cache/advent-2022` for the cache.
+    pub fn from_env() -> InputProvider {
+        let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let cache_dir = env::var_os(CACHE_DIR_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home_dir().join(".cache").join("advent-2022"));
+        let session = env::var(SESSION_VAR).ok().or_else(|| {
+            let config = home_dir()
+                .join(".config")
+                .join("advent-2022")
+                .join("session");
+            fs::read_to_string(config).ok()
+        });
+        InputProvider::new(
+            &base_url,
+            cache_dir,
+            session.map(|token| token.trim().to_string()),
+        )
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> InputProvider {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> InputProvider {
+        self.cache_dir = cache_dir;
+        self
+    }
+
+    pub(crate) fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Directory the inputs for this year are cached in, one `<day>.txt` each.
+    pub fn year_dir(&self) -> PathBuf {
+        self.cache_dir.join(YEAR.to_string())
+    }
+
+    pub(crate) fn session(&self) -> Result<&str> {
+        self.session.as_deref().ok_or_else(|| {
+            Error::Fetch(format!(
+                "no session token, set {} or write it to ~/.config/advent-2022/session",
+                SESSION_VAR
+            ))
+        })
+    }
+
+    pub fn cache_path(&self, day: usize) -> PathBuf {
+        self.cache_dir
+            .join(YEAR.to_string())
+            .join(format!("{}.txt", day))
+    }
+
+    /// Returns the path of the cached input for `day`, downloading it first if
+    /// it is not cached yet.
+    pub fn input(&self, day: usize) -> Result<PathBuf> {
+        let path = self.cache_path(day);
+        if path.exists() {
+            Ok(path)
+        } else {
+            self.fetch(day)
+        }
+    }
+
+    /// Downloads the input for `day` and stores it in the cache, replacing any
+    /// previously cached copy.
+    pub fn fetch(&self, day: usize) -> Result<PathBuf> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, YEAR, day);
+        let body = self.get(&url)?;
+        let path = self.cache_path(day);
+        write_atomically(&path, &body)?;
+        Ok(path)
+    }
+
+    fn get(&self, url: &str) -> Result<String> {
+        let response = client()?
+            .get(url)
+            .header(header::COOKIE, format!("session={}", self.session()?))
+            .send()
+            .map_err(|err| Error::Fetch(format!("{}: {}", url, err)))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::Fetch(format!("{} returned {}", url, status)));
+        }
+        response
+            .text()
+            .map_err(|err| Error::Fetch(format!("{}: {}", url, err)))
+    }
+}
+
+pub(crate) fn client() -> Result<Client> {
+    Client::builder()
+        .user_agent(concat!(
+            "github.com/AshtonKem/advent-2022 v",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()
+        .map_err(|err| Error::Fetch(err.to_string()))
+}
+
+fn home_dir() -> PathBuf {
+    env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let partial = path.with_extension("partial");
+    fs::write(&partial, contents)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    use super::*;
+
+    /// A request seen by the stand-in server.
+    pub(crate) struct Recorded {
+        pub(crate) request_line: String,
+        pub(crate) headers: Vec<String>,
+        pub(crate) body: String,
+    }
+
+    /// Serves each of `responses` (status, body) to one connection in turn and
+    /// records what was asked for. Returns the base URL to point clients at.
+    pub(crate) fn serve(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Recorded>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for (status, response) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut headers = Vec::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end().to_lowercase();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(length) = line.strip_prefix("content-length: ") {
+                        content_length = length.parse().unwrap();
+                    }
+                    headers.push(line);
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    response.len(),
+                    response
+                )
+                .unwrap();
+                sender
+                    .send(Recorded {
+                        request_line: request_line.trim_end().to_string(),
+                        headers,
+                        body: String::from_utf8(body).unwrap(),
+                    })
+                    .unwrap();
+            }
+        });
+        (format!("http://{}", address), receiver)
+    }
+
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("advent-2022-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetch_caches_input() {
+        let (base_url, requests) = serve(vec![(200, "1000\n2000\n")]);
+        let cache_dir = temp_dir("fetch");
+        let provider = InputProvider::new(&base_url, cache_dir.clone(), Some("abc".to_string()));
+
+        let path = provider.input(1).unwrap();
+        assert_eq!(cache_dir.join("2022").join("1.txt"), path);
+        assert_eq!("1000\n2000\n", fs::read_to_string(&path).unwrap());
+        let request = requests.recv().unwrap();
+        assert_eq!("GET /2022/day/1/input HTTP/1.1", request.request_line);
+        assert!(request.headers.iter().any(|h| h == "cookie: session=abc"));
+
+        // The server only answers once, so a second download would fail.
+        assert_eq!(path, provider.input(1).unwrap());
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_failure_is_not_cached() {
+        let (base_url, _requests) = serve(vec![(404, "not found")]);
+        let cache_dir = temp_dir("fetch-failure");
+        let provider = InputProvider::new(&base_url, cache_dir.clone(), Some("abc".to_string()));
+
+        assert!(matches!(provider.input(3), Err(Error::Fetch(_))));
+        assert!(!provider.cache_path(3).exists());
+    }
+
+    #[test]
+    fn test_missing_session() {
+        let provider = InputProvider::new("http://127.0.0.1:1", temp_dir("session"), None);
+        assert!(matches!(provider.fetch(1), Err(Error::Fetch(_))));
+    }
+}
@@ -0,0 +1,465 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+/// Integer types intervals can be built over.
+pub trait Discrete: Copy + Ord {
+    fn successor(self) -> Option<Self>;
+    fn predecessor(self) -> Option<Self>;
+    /// How many values lie in `start..=end`, saturating at `u64::MAX`.
+    fn count(start: Self, end: Self) -> u64;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(
+            impl Discrete for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn count(start: Self, end: Self) -> u64 {
+                    let count = end as i128 - start as i128 + 1;
+                    u64::try_from(count).unwrap_or(u64::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_discrete!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// The inclusive range `start..=end`, never empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Discrete> Interval<T> {
+    /// `None` if `end` comes before `start`.
+    pub fn new(start: T, end: T) -> Option<Interval<T>> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn single(value: T) -> Interval<T> {
+        Interval {
+            start: value,
+            end: value,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    // An interval always holds at least its start, so there is no is_empty.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        T::count(self.start, self.end)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Whether every value of `other` is also in `self`.
+    pub fn covers(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Whether the two share a value or `other` starts right after `self`
+    /// ends, or the other way round.
+    pub fn touches(&self, other: &Interval<T>) -> bool {
+        self.overlaps(other)
+            || self.end.successor() == Some(other.start)
+            || other.end.successor() == Some(self.start)
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The single interval covering both, `None` if there would be a gap.
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        self.touches(other).then(|| Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+
+    /// The values of `self` not in `other`, at most one interval either side.
+    pub fn difference(&self, other: &Interval<T>) -> Vec<Interval<T>> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+        let before = other
+            .start
+            .predecessor()
+            .and_then(|end| Interval::new(self.start, end));
+        let after = other
+            .end
+            .successor()
+            .and_then(|start| Interval::new(start, self.end));
+        before.into_iter().chain(after).collect()
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of values kept as sorted intervals that neither overlap nor touch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// How many values the set holds.
+    pub fn len(&self) -> u64 {
+        self.intervals
+            .iter()
+            .map(Interval::len)
+            .fold(0, u64::saturating_add)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.end < value);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // Everything from `first` to `last` touches the new interval and is
+        // merged into it.
+        let first = self.intervals.partition_point(|existing| {
+            existing.end < interval.start && !existing.touches(&interval)
+        });
+        let mut merged = interval;
+        let mut last = first;
+        while let Some(union) = self
+            .intervals
+            .get(last)
+            .and_then(|existing| existing.union(&merged))
+        {
+            merged = union;
+            last += 1;
+        }
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for interval in &other.intervals {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            if let Some(shared) = a.intersection(b) {
+                intervals.push(shared);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        for interval in &self.intervals {
+            let mut remaining = vec![*interval];
+            for removed in other.intervals.iter().filter(|r| r.overlaps(interval)) {
+                remaining = remaining
+                    .iter()
+                    .flat_map(|piece| piece.difference(removed))
+                    .collect();
+            }
+            intervals.extend(remaining);
+        }
+        IntervalSet { intervals }
+    }
+}
+
+impl<T: Discrete> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut intervals: Vec<Interval<T>> = iter.into_iter().collect();
+        intervals.sort();
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged
+                .last_mut()
+                .and_then(|last| Some((last.union(&interval)?, last)))
+            {
+                Some((union, last)) => *last = union,
+                None => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+}
+
+impl<T: Display> Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, interval) in self.intervals.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", interval)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits the span from the lowest start to the highest end into runs of
+/// values covered by the same number of intervals, in order. Runs nothing
+/// covers are included with a depth of 0.
+pub fn depths<T: Discrete>(intervals: &[Interval<T>]) -> Vec<(Interval<T>, usize)> {
+    let mut starts: Vec<T> = intervals.iter().map(Interval::start).collect();
+    let mut ends: Vec<T> = intervals.iter().map(Interval::end).collect();
+    starts.sort_unstable();
+    ends.sort_unstable();
+    let mut runs = Vec::new();
+    let (mut started, mut ended, mut depth) = (0, 0, 0);
+    let Some(mut position) = starts.first().copied() else {
+        return runs;
+    };
+    loop {
+        while started < starts.len() && starts[started] == position {
+            started += 1;
+            depth += 1;
+        }
+        while ended < ends.len() && ends[ended] < position {
+            ended += 1;
+            depth -= 1;
+        }
+        let Some(&next_end) = ends.get(ended) else {
+            break;
+        };
+        // The run lasts until the next interval ends or starts, whichever
+        // comes first.
+        let mut end = next_end;
+        if depth == 0 || starts.get(started).is_some_and(|start| *start <= end) {
+            let next_start = starts[started];
+            end = end.min(
+                next_start
+                    .predecessor()
+                    .expect("a later start has a predecessor"),
+            );
+        }
+        runs.push((
+            Interval {
+                start: position,
+                end,
+            },
+            depth,
+        ));
+        match end.successor() {
+            Some(next) => position = next,
+            None => break,
+        }
+    }
+    runs
+}
+
+/// The largest number of intervals sharing a single value.
+pub fn max_depth<T: Discrete>(intervals: &[Interval<T>]) -> usize {
+    let mut starts: Vec<T> = intervals.iter().map(Interval::start).collect();
+    let mut ends: Vec<T> = intervals.iter().map(Interval::end).collect();
+    starts.sort_unstable();
+    ends.sort_unstable();
+    let (mut depth, mut deepest, mut closed) = (0, 0, 0);
+    for start in starts {
+        // Intervals ending before this one starts no longer count.
+        while ends[closed] < start {
+            closed += 1;
+            depth -= 1;
+        }
+        depth += 1;
+        deepest = deepest.max(depth);
+    }
+    deepest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: i32, end: i32) -> Interval<i32> {
+        Interval::new(start, end).unwrap()
+    }
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|(start, end)| interval(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        assert!(Interval::new(3, 2).is_none());
+        assert_eq!(5, interval(2, 6).len());
+        assert_eq!(1, Interval::single(7u8).len());
+        assert_eq!(256, Interval::new(0u8, 255).unwrap().len());
+        assert!(interval(2, 8).covers(&interval(3, 7)));
+        assert!(!interval(3, 7).covers(&interval(2, 8)));
+        assert!(interval(1, 3).overlaps(&interval(3, 4)));
+        assert!(!interval(1, 2).overlaps(&interval(3, 4)));
+        assert!(interval(1, 2).touches(&interval(3, 4)));
+        assert_eq!(
+            Some(interval(3, 3)),
+            interval(1, 3).intersection(&interval(3, 4))
+        );
+        assert_eq!(None, interval(1, 2).intersection(&interval(3, 4)));
+        assert_eq!(Some(interval(1, 4)), interval(1, 2).union(&interval(3, 4)));
+        assert_eq!(None, interval(1, 2).union(&interval(4, 5)));
+        assert_eq!("-2-3", interval(-2, 3).to_string());
+    }
+
+    #[test]
+    fn test_difference() {
+        assert_eq!(
+            vec![interval(1, 2), interval(6, 9)],
+            interval(1, 9).difference(&interval(3, 5))
+        );
+        assert_eq!(
+            vec![interval(6, 9)],
+            interval(1, 9).difference(&interval(0, 5))
+        );
+        assert!(interval(3, 5).difference(&interval(1, 9)).is_empty());
+        assert_eq!(
+            vec![interval(1, 2)],
+            interval(1, 2).difference(&interval(5, 6))
+        );
+        let edge = Interval::new(0u8, 255).unwrap();
+        assert_eq!(
+            vec![Interval::new(1u8, 255).unwrap()],
+            edge.difference(&Interval::single(0))
+        );
+    }
+
+    #[test]
+    fn test_set_insert() {
+        let mut intervals = set(&[(1, 2), (5, 6), (10, 12)]);
+        assert_eq!(7, intervals.len());
+        intervals.insert(interval(3, 3));
+        assert_eq!(set(&[(1, 3), (5, 6), (10, 12)]), intervals);
+        intervals.insert(interval(4, 10));
+        assert_eq!(set(&[(1, 12)]), intervals);
+        intervals.insert(interval(20, 20));
+        intervals.insert(interval(15, 15));
+        assert_eq!("1-12,15-15,20-20", intervals.to_string());
+        assert!(intervals.contains(15) && !intervals.contains(14) && !intervals.contains(21));
+        assert_eq!(set(&[(1, 4), (3, 9), (11, 11)]), set(&[(1, 9), (11, 11)]));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 5), (10, 15)]);
+        let b = set(&[(4, 11), (14, 20)]);
+        assert_eq!(set(&[(1, 20)]), a.union(&b));
+        assert_eq!(set(&[(4, 5), (10, 11), (14, 15)]), a.intersection(&b));
+        assert_eq!(set(&[(1, 3), (12, 13)]), a.difference(&b));
+        assert_eq!(set(&[(6, 9), (16, 20)]), b.difference(&a));
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_depths() {
+        let intervals = [
+            interval(1, 5),
+            interval(3, 4),
+            interval(8, 9),
+            interval(9, 12),
+        ];
+        let runs: Vec<(String, usize)> = depths(&intervals)
+            .iter()
+            .map(|(run, depth)| (run.to_string(), *depth))
+            .collect();
+        let expected = [
+            ("1-2", 1),
+            ("3-4", 2),
+            ("5-5", 1),
+            ("6-7", 0),
+            ("8-8", 1),
+            ("9-9", 2),
+            ("10-12", 1),
+        ];
+        assert_eq!(
+            expected
+                .iter()
+                .map(|(run, depth)| (run.to_string(), *depth))
+                .collect::<Vec<_>>(),
+            runs
+        );
+        let edge = [Interval::new(250u8, 255).unwrap(), Interval::single(255)];
+        assert_eq!(
+            vec![
+                (Interval::new(250u8, 254).unwrap(), 1),
+                (Interval::single(255), 2)
+            ],
+            depths(&edge)
+        );
+        assert!(depths::<i32>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let intervals = [
+            interval(1, 5),
+            interval(5, 8),
+            interval(2, 3),
+            interval(9, 9),
+        ];
+        assert_eq!(2, max_depth(&intervals));
+        assert_eq!(
+            3,
+            max_depth(&[interval(1, 5), interval(2, 6), interval(5, 5)])
+        );
+        assert_eq!(0, max_depth::<i32>(&[]));
+    }
+}
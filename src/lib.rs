@@ -0,0 +1,12 @@
+pub mod bench;
+pub mod days;
+pub mod error;
+pub mod input;
+pub mod interval;
+pub mod runner;
+pub mod solution;
+pub mod submit;
+pub mod verify;
+
+pub use error::{Error, Result};
+pub use solution::{Day, Part, Solution};
@@ -1,100 +1,846 @@
-mod days;
-pub mod utils;
+use advent_2022::{
+    bench, days,
+    days::day_five::{
+        self,
+        checkpoint::{self, Checkpoint},
+        crane::Model,
+        DayFive,
+    },
+    days::day_four::{self, roster, DayFour},
+    days::day_one::{self, DayOne, Stat},
+    days::day_six::{
+        self,
+        stream::{self, Event},
+    },
+    days::day_three,
+    days::day_two::{
+        self,
+        analysis::{self, Analysis, Reading},
+        rules::{self, Decoder, Variant},
+    },
+    input::InputProvider,
+    runner,
+    submit::Submitter,
+    verify,
+    verify::Answers,
+    Day, Part, Solution,
+};
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, IsTerminal, Read},
+    path::PathBuf,
+};
 
-/// Simple program to greet a person
+/// Solutions for Advent of Code 2022
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[command(subcommand)]
     command: Commands,
+    /// Server to fetch inputs from and submit answers to, defaults to $AOC_BASE_URL or adventofcode.com
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+    /// Directory inputs and verdicts are kept in, defaults to $AOC_CACHE_DIR or ~/.cache/advent-2022
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+}
+
+/// Where a single day's input is read from. `-` reads standard input.
+/// Without a path, piped input is read if there is any, otherwise the input
+/// cache is used, so an empty or `/dev/null` stdin under cron or CI still
+/// finds the cached input.
+#[derive(clap::Args, Debug)]
+struct InputArg {
+    /// Input file or - for stdin, piped stdin or the cached (or freshly downloaded) input is used if omitted
+    path: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-    DayOne {
+    /// Run a single day against an input file
+    Run {
         #[arg(required = true)]
-        path: PathBuf,
+        day: usize,
+        #[command(flatten)]
+        input: InputArg,
+        /// Only run the given part (1 or 2), runs both by default
         #[arg(short, long)]
-        bonus: bool,
+        part: Option<Part>,
+        /// Run the puzzle's worked examples and check their answers instead
+        #[arg(short, long, conflicts_with = "path")]
+        example: bool,
     },
-    DayTwo {
-        #[arg(required = true)]
-        path: PathBuf,
+    /// Summarise day one's calorie inventory
+    Calories {
+        #[command(flatten)]
+        input: InputArg,
+        /// Show the N largest totals and their sum
         #[arg(short, long)]
-        bonus: bool,
+        top: Option<usize>,
+        /// Statistic to show: elves, min, max, sum, mean, median or a percentile like p90
+        #[arg(short, long)]
+        stat: Vec<Stat>,
+        /// Find the --top totals while reading, in constant memory, for inputs too large to hold
+        #[arg(long, requires = "top", conflicts_with = "stat")]
+        stream: bool,
     },
-    DayThree {
-        #[arg(required = true)]
-        path: PathBuf,
+    /// Score day two's strategy guide under a rule set, reading it both as hands and as outcomes
+    Strategy {
+        #[command(flatten)]
+        input: InputArg,
+        /// TOML file describing the game and symbols, plain rock, paper, scissors by default
+        #[arg(short, long)]
+        rules: Option<PathBuf>,
+        /// Score every possible decoding of the strategy column instead of the fixed ones
         #[arg(short, long)]
-        bonus: bool,
+        analyse: bool,
+        /// Rounds listed as swinging the score most when analysing
+        #[arg(long, default_value_t = 5)]
+        swings: usize,
     },
-    DayFour {
-        #[arg(required = true)]
-        path: PathBuf,
+    /// Sum day three's rucksack priorities, or check every rucksack for problems
+    Rucksacks {
+        #[command(flatten)]
+        input: InputArg,
+        /// Rucksacks per group sharing a badge
+        #[arg(
+            short,
+            long,
+            default_value_t = day_three::GROUP_SIZE,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        group_size: usize,
+        /// List every problem found, by line, instead of summing
         #[arg(short, long)]
-        bonus: bool,
+        diagnose: bool,
     },
-    DayFive {
-        #[arg(required = true)]
-        path: PathBuf,
+    /// Summarise which sections day four's elves are assigned across the whole input
+    Sections {
+        #[command(flatten)]
+        input: InputArg,
+        /// Report gaps, the most contended sections and every elf's overlaps across the roster
         #[arg(short, long)]
-        bonus: bool,
+        roster: bool,
+        /// With --roster, also list which other lines overlap each elf
+        #[arg(short, long, requires = "roster")]
+        lines: bool,
+        /// With --roster, print the report as JSON instead of a table
+        #[arg(long, requires = "roster")]
+        json: bool,
     },
-    DaySix {
-        #[arg(required = true)]
-        path: PathBuf,
+    /// Rearrange day five's crates with one or more crane models
+    Crates {
+        #[command(flatten)]
+        input: InputArg,
+        /// Crane to use: single (9000), multi (9001), bottom or limited:K, both CrateMovers by default
         #[arg(short, long)]
-        bonus: bool,
+        crane: Vec<Model>,
+        /// Draw the stacks before the first move and after every N moves, 1 if N is omitted
+        #[arg(short, long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+        trace: Option<usize>,
+        /// Write the state and the moves left to this JSON file, needs a single --crane
+        #[arg(long, value_name = "FILE")]
+        checkpoint: Option<PathBuf>,
+        /// Only carry out the first N moves, to --checkpoint part way through
+        #[arg(long, value_name = "N", requires = "checkpoint")]
+        stop_after: Option<usize>,
+        /// Start from a JSON checkpoint instead of the puzzle input
+        #[arg(long, value_name = "FILE", conflicts_with = "path")]
+        from_state: Option<PathBuf>,
+        /// List the stacks that differ from the state in this JSON checkpoint
+        #[arg(long, value_name = "FILE")]
+        diff: Option<PathBuf>,
     },
-    DaySeven {
-        #[arg(required = true)]
-        path: PathBuf,
+    /// List every marker and payload in day six's signal, reading it as a stream
+    Signal {
+        #[command(flatten)]
+        input: InputArg,
+        /// Distinct bytes in a start-of-packet marker
+        #[arg(long, default_value_t = day_six::PACKET_MARKER)]
+        packet: usize,
+        /// Distinct bytes in a start-of-message marker
+        #[arg(long, default_value_t = day_six::MESSAGE_MARKER)]
+        message: usize,
+        /// Only list markers, not the payloads between them
         #[arg(short, long)]
-        bonus: bool,
+        markers: bool,
     },
-    DayEight {
+    /// Download a day's input into the cache
+    Fetch {
         #[arg(required = true)]
-        path: PathBuf,
+        day: usize,
+        /// Download again even if the input is already cached
         #[arg(short, long)]
-        bonus: bool,
+        force: bool,
     },
-    DayNine {
-        #[arg(required = true)]
-        path: PathBuf,
+    /// Run both parts of every day and print a summary
+    All {
+        /// Directory holding one <day>.txt input per day, defaults to the input cache
         #[arg(short, long)]
-        bonus: bool,
+        input_dir: Option<PathBuf>,
+        /// Print the results as JSON instead of a table
+        #[arg(long)]
+        json: bool,
     },
-    DayTen {
-        #[arg(required = true)]
-        path: PathBuf,
+    /// Time parsing and solving for each day and part
+    Bench {
+        /// Only benchmark this day, all days are benchmarked by default
+        day: Option<usize>,
+        /// Directory holding one <day>.txt input per day, defaults to the input cache
+        #[arg(short, long)]
+        input_dir: Option<PathBuf>,
+        /// Timed runs per part
+        #[arg(short = 'n', long, default_value_t = 20)]
+        iterations: usize,
+        /// Untimed runs per part before timing starts
+        #[arg(short, long, default_value_t = 3)]
+        warmup: usize,
+        /// Write the results to this file for later comparison
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Compare against results previously written with --save
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Slowdown of the median tolerated before flagging a regression, in percent
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Run every day and compare the answers against a stored answers file
+    Verify {
+        /// Directory holding one <day>.txt input per day, defaults to the input cache
+        #[arg(short, long)]
+        input_dir: Option<PathBuf>,
+        /// TOML file of expected answers, defaults to answers.toml in the input directory
         #[arg(short, long)]
-        bonus: bool,
+        answers: Option<PathBuf>,
+        /// Store the current answers for every day and part instead of checking them
+        #[arg(long)]
+        record: bool,
+        /// Succeed even if some answers have nothing stored to check them against
+        #[arg(long, conflicts_with = "record")]
+        allow_unchecked: bool,
+    },
+    /// Run a day and submit the answer for one part
+    Submit {
+        #[arg(required = true)]
+        day: usize,
+        #[arg(required = true)]
+        part: Part,
+        #[command(flatten)]
+        input: InputArg,
     },
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn provider(args: &Args) -> InputProvider {
+    let mut provider = InputProvider::from_env();
+    if let Some(base_url) = &args.base_url {
+        provider = provider.with_base_url(base_url);
+    }
+    if let Some(cache_dir) = &args.cache_dir {
+        provider = provider.with_cache_dir(cache_dir.clone());
+    }
+    provider
+}
 
+fn solution_for(day: usize) -> &'static dyn Day {
+    days::get(day).unwrap_or_else(|| {
+        fail(format!(
+            "Unknown day {}, expected 1 to {}",
+            day,
+            days::count()
+        ))
+    })
+}
+
+/// Where a single day's input comes from.
+enum Source {
+    File(PathBuf),
+    /// Piped input, read once so both parts can see it.
+    Stdin(String),
+}
+
+impl Source {
+    fn stdin() -> Source {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .unwrap_or_else(|err| fail(format!("Could not read stdin: {}", err)));
+        Source::Stdin(input)
+    }
+
+    fn run(&self, solution: &dyn Day, part: Part) -> advent_2022::Result<String> {
+        match self {
+            Source::File(path) => solution.run(path, part),
+            Source::Stdin(input) => solution.run_str(input, part),
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Source::File(path) => std::fs::read_to_string(path)
+                .unwrap_or_else(|err| fail(format!("{}: {}", path.display(), err))),
+            Source::Stdin(input) => input.clone(),
+        }
+    }
+
+    fn parse<S: Solution>(&self, solution: &S) -> advent_2022::Result<S::Input> {
+        match self {
+            Source::File(path) => solution.parse(&mut BufReader::new(File::open(path)?)),
+            Source::Stdin(input) => solution.parse(&mut input.as_bytes()),
+        }
+    }
+}
+
+/// Whether stdin is piped and has something to read, checked without
+/// consuming any of it.
+fn piped_stdin() -> bool {
+    let mut stdin = io::stdin().lock();
+    !stdin.is_terminal()
+        && !stdin
+            .fill_buf()
+            .unwrap_or_else(|err| fail(format!("Could not read stdin: {}", err)))
+            .is_empty()
+}
+
+fn cached_input(args: &Args, day: usize) -> PathBuf {
+    provider(args)
+        .input(day)
+        .unwrap_or_else(|err| fail(format!("Day {}: {}", day, err)))
+}
+
+impl InputArg {
+    /// The file to read, `None` for standard input.
+    fn file(&self, args: &Args, day: usize) -> Option<PathBuf> {
+        match &self.path {
+            Some(path) if path.as_os_str() == "-" => None,
+            Some(path) => Some(path.clone()),
+            None if piped_stdin() => None,
+            None => Some(cached_input(args, day)),
+        }
+    }
+
+    fn source(&self, args: &Args, day: usize) -> Source {
+        match self.file(args, day) {
+            Some(path) => Source::File(path),
+            None => Source::stdin(),
+        }
+    }
+
+    /// Opens the input for streaming, without reading it into memory first.
+    fn reader(&self, args: &Args, day: usize) -> Box<dyn BufRead> {
+        match self.file(args, day) {
+            None => Box::new(io::stdin().lock()),
+            Some(path) => {
+                Box::new(BufReader::new(File::open(&path).unwrap_or_else(|err| {
+                    fail(format!("{}: {}", path.display(), err))
+                })))
+            }
+        }
+    }
+}
+
+fn print_top(top: usize, totals: &[u32]) {
+    let listed: Vec<String> = totals.iter().map(u32::to_string).collect();
+    println!(
+        "top {}: {} (sum {})",
+        top,
+        listed.join(" "),
+        totals.iter().map(|total| u64::from(*total)).sum::<u64>()
+    );
+}
+
+fn print_analysis(analysis: &Analysis, rounds: &[(String, String)], swings: usize) {
+    println!(
+        "as {}, {} decodings:",
+        analysis.reading,
+        analysis.scored.len()
+    );
+    println!("  best  {}", analysis.best());
+    println!("  worst {}", analysis.worst());
+    let distribution: Vec<String> = analysis
+        .distribution()
+        .iter()
+        .rev()
+        .map(|(score, count)| format!("{} x{}", score, count))
+        .collect();
+    println!("  scores {}", distribution.join(", "));
+    for swing in analysis.swings.iter().take(swings) {
+        let (theirs, ours) = &rounds[swing.round - 1];
+        println!(
+            "  round {} ({} {}) swings {}, from {} to {}",
+            swing.round,
+            theirs,
+            ours,
+            swing.swing(),
+            swing.worst,
+            swing.best
+        );
+    }
+}
+
+/// Prints the answers for every example, exiting with an error if any of
+/// them differs from the answer the puzzle gives.
+fn run_examples(day: usize, solution: &dyn Day, parts: &[Part]) {
+    let examples = solution.examples();
+    if examples.is_empty() {
+        fail(format!("Day {} has no examples", day));
+    }
+    let mut mismatches = 0;
+    for (index, example) in examples.iter().enumerate() {
+        if examples.len() > 1 {
+            println!("example {}:", index + 1);
+        }
+        for part in parts {
+            let answer = solution
+                .run_str(example.input, *part)
+                .unwrap_or_else(|err| {
+                    fail(format!(
+                        "Day {} example {} part {} failed: {}",
+                        day,
+                        index + 1,
+                        part,
+                        err
+                    ))
+                });
+            println!("{}", answer);
+            match example.expected(*part) {
+                Some(expected) if expected != answer => {
+                    eprintln!(
+                        "Day {} example {} part {}: expected {:?}",
+                        day,
+                        index + 1,
+                        part,
+                        expected
+                    );
+                    mismatches += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    if mismatches > 0 {
+        std::process::exit(1);
+    }
 }
 
 fn main() {
     let args = Args::parse();
 
     match &args.command {
-        Commands::DayOne { path, bonus } => {
-            println!("{}", days::day_one::run(path, bonus.to_owned()));
-        }
-        Commands::DayTwo { path, bonus } => {
-            println!("{}", days::day_two::run(path, bonus.to_owned()));
-        }
-        Commands::DayThree { path, bonus } => {
-            println!("{}", days::day_three::run(path, bonus.to_owned()));
-        }
-        Commands::DayFour { path, bonus } => println!("{}", days::day_four::run(path, bonus.to_owned())),
-        Commands::DayFive { path, bonus } => println!("{}", days::day_five::run(path, bonus.to_owned())),
-        Commands::DaySix { path, bonus } => println!("{}", days::day_six::run(path, bonus.to_owned())),
-        Commands::DaySeven { path, bonus } => println!("{}", days::day_seven::run(path, bonus.to_owned())),
-        Commands::DayEight { path, bonus } => println!("{}", days::day_eight::run(path, bonus.to_owned())),
-        Commands::DayNine { path, bonus } => println!("{}", days::day_nine::run(path, bonus.to_owned())),
-        Commands::DayTen { path, bonus } => println!("{}", days::day_ten::run(path, bonus.to_owned())),
+        Commands::Run {
+            day,
+            input,
+            part,
+            example,
+        } => {
+            let solution = solution_for(*day);
+            let parts = match part {
+                Some(part) => vec![*part],
+                None => vec![Part::One, Part::Two],
+            };
+            if *example {
+                run_examples(*day, solution, &parts);
+                return;
+            }
+            let source = input.source(&args, *day);
+            for part in parts {
+                match source.run(solution, part) {
+                    Ok(answer) => println!("{}", answer),
+                    Err(err) => fail(format!("Day {} part {} failed: {}", day, part, err)),
+                }
+            }
+        }
+        Commands::Calories {
+            input,
+            top,
+            stat: _,
+            stream: true,
+        } => {
+            let top = top.expect("--stream requires --top");
+            let totals = day_one::stream_top(&mut *input.reader(&args, 1), top)
+                .unwrap_or_else(|err| fail(format!("Day 1: {}", err)));
+            print_top(top, &totals);
+        }
+        Commands::Calories {
+            input,
+            top,
+            stat,
+            stream: false,
+        } => {
+            let inventory = input
+                .source(&args, 1)
+                .parse(&DayOne)
+                .unwrap_or_else(|err| fail(format!("Day 1: {}", err)));
+            let stats = if stat.is_empty() && top.is_none() {
+                vec![Stat::Elves, Stat::Min, Stat::Max, Stat::Mean, Stat::Median]
+            } else {
+                stat.clone()
+            };
+            if let Some(top) = top {
+                print_top(*top, &inventory.top(*top));
+            }
+            for stat in stats {
+                match inventory.stat(stat) {
+                    Some(value) => println!("{}: {}", stat, value),
+                    None => println!("{}: no elves", stat),
+                }
+            }
+        }
+        Commands::Strategy {
+            input,
+            rules,
+            analyse,
+            swings,
+        } => {
+            let variant = match rules {
+                Some(rules) => Variant::load(rules).unwrap_or_else(|err| fail(err.to_string())),
+                None => Variant::standard(),
+            };
+            let source = input.source(&args, 2);
+            let rounds = day_two::parse_rounds(&mut source.text().as_bytes())
+                .unwrap_or_else(|err| fail(format!("Day 2: {}", err)));
+            if *analyse {
+                for reading in [Reading::Hands, Reading::Outcomes] {
+                    match analysis::analyse(&variant.rules, &rounds, reading) {
+                        Ok(analysis) => print_analysis(&analysis, &rounds, *swings),
+                        Err(err) => println!("as {}: {}", reading, err),
+                    }
+                }
+                return;
+            }
+            for (name, decoder) in [
+                ("as hands", &variant.hands as &dyn Decoder),
+                ("as outcomes", &variant.outcomes),
+            ] {
+                match rules::play(&variant.rules, decoder, &rounds) {
+                    Ok(score) => println!("{}: {}", name, score),
+                    Err(err) => println!("{}: {}", name, err),
+                }
+            }
+        }
+        Commands::Rucksacks {
+            input,
+            group_size,
+            diagnose,
+        } => {
+            let sacks: Vec<String> = input
+                .source(&args, 3)
+                .text()
+                .lines()
+                .map(str::to_string)
+                .collect();
+            if *diagnose {
+                let findings = day_three::diagnose(&sacks, *group_size)
+                    .unwrap_or_else(|err| fail(err.to_string()));
+                for finding in &findings {
+                    println!("{}", finding);
+                }
+                if !findings.is_empty() {
+                    fail(format!("{} problems found", findings.len()));
+                }
+                println!("{} rucksacks, no problems found", sacks.len());
+                return;
+            }
+            let mut compartments = 0;
+            for (index, sack) in sacks.iter().enumerate() {
+                compartments += day_three::process(sack)
+                    .unwrap_or_else(|problem| fail(format!("line {}: {}", index + 1, problem)));
+            }
+            println!("compartments: {}", compartments);
+            match day_three::badge_sum(&sacks, *group_size) {
+                Ok(badges) => println!("badges: {}", badges),
+                Err(err) => fail(err.to_string()),
+            }
+        }
+        Commands::Sections {
+            input,
+            roster,
+            lines,
+            json,
+        } => {
+            let pairs = input
+                .source(&args, 4)
+                .parse(&DayFour)
+                .unwrap_or_else(|err| fail(format!("Day 4: {}", err)));
+            if *roster {
+                let coverage = roster::coverage(&pairs, *lines);
+                if *json {
+                    let output = serde_json::to_string_pretty(&coverage)
+                        .unwrap_or_else(|err| fail(format!("Could not encode results: {}", err)));
+                    println!("{}", output);
+                } else {
+                    print!("{}", roster::format_table(&coverage));
+                }
+                return;
+            }
+            let covered = day_four::sections_covered(&pairs);
+            let shared = day_four::sections_shared(&pairs);
+            println!("covered: {} sections ({})", covered.len(), covered);
+            println!("shared: {} sections ({})", shared.len(), shared);
+            println!(
+                "deepest overlap: {} elves",
+                day_four::max_overlap_depth(&pairs)
+            );
+        }
+        Commands::Crates {
+            input,
+            crane,
+            trace,
+            checkpoint,
+            stop_after,
+            from_state,
+            diff,
+        } => {
+            let start = match from_state {
+                Some(file) => Checkpoint::load(file).unwrap_or_else(|err| fail(err.to_string())),
+                None => {
+                    let (state, moves) = input
+                        .source(&args, 5)
+                        .parse(&DayFive)
+                        .unwrap_or_else(|err| fail(format!("Day 5: {}", err)));
+                    Checkpoint::new(state, moves)
+                }
+            };
+            let against = diff.as_ref().map(|file| {
+                Checkpoint::load(file)
+                    .unwrap_or_else(|err| fail(err.to_string()))
+                    .state
+            });
+            let models = if crane.is_empty() {
+                vec![Model::Single, Model::Multi]
+            } else {
+                crane.clone()
+            };
+            if checkpoint.is_some() && models.len() != 1 {
+                fail("--checkpoint needs exactly one --crane".to_string());
+            }
+            let stop = stop_after
+                .unwrap_or(start.moves.len())
+                .min(start.moves.len());
+            let (moves, rest) = start.moves.split_at(stop);
+            for model in models {
+                let result = match trace {
+                    Some(every) => {
+                        let every = (*every).max(1);
+                        println!("{}, start:\n{}", model, start.state);
+                        day_five::replay(&start.state, moves, model.crane(), |step, state| {
+                            if step % every == 0 || step == moves.len() {
+                                println!(
+                                    "{}, move {} ({}):\n{}",
+                                    model,
+                                    start.done + step,
+                                    moves[step - 1],
+                                    state
+                                );
+                            }
+                        })
+                    }
+                    None => day_five::simulate(&start.state, moves, model.crane()),
+                };
+                let state = match result {
+                    Ok(state) => state,
+                    Err(err) => {
+                        println!("{}: {}", model, err);
+                        continue;
+                    }
+                };
+                println!("{}: {}", model, state.final_state());
+                if let Some(against) = &against {
+                    for stack in checkpoint::diff(&state, against) {
+                        println!("  {}", stack);
+                    }
+                }
+                if let Some(file) = checkpoint {
+                    let paused = Checkpoint {
+                        done: start.done + moves.len(),
+                        state,
+                        moves: rest.to_vec(),
+                    };
+                    paused
+                        .save(file)
+                        .unwrap_or_else(|err| fail(format!("{}: {}", file.display(), err)));
+                }
+            }
+        }
+        Commands::Signal {
+            input,
+            packet,
+            message,
+            markers,
+        } => {
+            let mut decoder = stream::Decoder::new(*packet, *message)
+                .unwrap_or_else(|| fail("Marker sizes must be at least 1".to_string()));
+            let mut found = [0, 0];
+            let length = stream::decode(&mut decoder, &mut *input.reader(&args, 6), |event| {
+                match event {
+                    Event::Marker { kind, .. } => found[kind as usize] += 1,
+                    Event::Payload { .. } if *markers => return,
+                    Event::Payload { .. } => {}
+                }
+                println!("{}", event);
+            })
+            .unwrap_or_else(|err| fail(format!("Day 6: {}", err)));
+            println!(
+                "{} bytes, {} packet markers, {} message markers",
+                length, found[0], found[1]
+            );
+        }
+        Commands::Fetch { day, force } => {
+            solution_for(*day);
+            let provider = provider(&args);
+            let result = if *force {
+                provider.fetch(*day)
+            } else {
+                provider.input(*day)
+            };
+            match result {
+                Ok(path) => println!("{}", path.display()),
+                Err(err) => fail(format!("Day {}: {}", day, err)),
+            }
+        }
+        Commands::All { input_dir, json } => {
+            let input_dir = input_dir
+                .clone()
+                .unwrap_or_else(|| provider(&args).year_dir());
+            let reports = runner::run_all(&input_dir);
+            if *json {
+                let output = serde_json::to_string_pretty(&reports)
+                    .unwrap_or_else(|err| fail(format!("Could not encode results: {}", err)));
+                println!("{}", output);
+            } else {
+                print!("{}", runner::format_table(&reports));
+            }
+            if !reports.iter().all(runner::Report::succeeded) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Bench {
+            day,
+            input_dir,
+            iterations,
+            warmup,
+            save,
+            baseline,
+            threshold,
+        } => {
+            let input_dir = input_dir
+                .clone()
+                .unwrap_or_else(|| provider(&args).year_dir());
+            let selected: Vec<usize> = match day {
+                Some(day) => {
+                    solution_for(*day);
+                    vec![*day]
+                }
+                None => (1..=days::count()).collect(),
+            };
+            let mut measurements = Vec::new();
+            for day in selected {
+                let path = input_dir.join(format!("{}.txt", day));
+                for part in [Part::One, Part::Two] {
+                    let result =
+                        bench::measure(day, solution_for(day), &path, part, *warmup, *iterations);
+                    match result {
+                        Ok(measurement) => {
+                            println!("day {} part {}", day, part);
+                            println!("  parse: {}", measurement.parse);
+                            println!("  solve: {}", measurement.solve);
+                            measurements.push(measurement);
+                        }
+                        Err(err) => eprintln!("day {} part {} failed: {}", day, part, err),
+                    }
+                }
+            }
+            if let Some(save) = save {
+                bench::save_baseline(save, &measurements)
+                    .unwrap_or_else(|err| fail(format!("{}: {}", save.display(), err)));
+            }
+            if let Some(baseline) = baseline {
+                let previous =
+                    bench::load_baseline(baseline).unwrap_or_else(|err| fail(err.to_string()));
+                let regressions = bench::regressions(&previous, &measurements, threshold / 100.0);
+                for regression in &regressions {
+                    println!("{}", regression);
+                }
+                if !regressions.is_empty() {
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Verify {
+            input_dir,
+            answers,
+            record,
+            allow_unchecked,
+        } => {
+            let input_dir = input_dir
+                .clone()
+                .unwrap_or_else(|| provider(&args).year_dir());
+            let answers_path = answers
+                .clone()
+                .unwrap_or_else(|| input_dir.join("answers.toml"));
+            let reports = runner::run_all(&input_dir);
+            if *record {
+                let mut answers = if answers_path.exists() {
+                    Answers::load(&answers_path)
+                        .unwrap_or_else(|err| fail(format!("{}: {}", answers_path.display(), err)))
+                } else {
+                    Answers::default()
+                };
+                verify::record(&reports, &mut answers);
+                answers
+                    .save(&answers_path)
+                    .unwrap_or_else(|err| fail(format!("{}: {}", answers_path.display(), err)));
+                println!("Recorded answers in {}", answers_path.display());
+                return;
+            }
+            let answers = Answers::load(&answers_path)
+                .unwrap_or_else(|err| fail(format!("{}: {}", answers_path.display(), err)));
+            let checks = verify::check(&reports, &answers);
+            for check in &checks {
+                println!("{}", check);
+            }
+            let unchecked = checks.iter().filter(|check| check.is_unchecked()).count();
+            let failures = checks
+                .iter()
+                .filter(|check| !check.passed() && !check.is_unchecked())
+                .count();
+            if unchecked > 0 {
+                eprintln!(
+                    "{} of {} answers have no stored answer, store them with --record",
+                    unchecked,
+                    checks.len()
+                );
+            }
+            if failures > 0 {
+                fail(format!(
+                    "{} of {} answers did not verify",
+                    failures,
+                    checks.len()
+                ));
+            }
+            if unchecked > 0 && !allow_unchecked {
+                std::process::exit(1);
+            }
+        }
+        Commands::Submit { day, part, input } => {
+            let solution = solution_for(*day);
+            let source = input.source(&args, *day);
+            let answer = source
+                .run(solution, *part)
+                .unwrap_or_else(|err| fail(format!("Day {} part {} failed: {}", day, part, err)));
+            println!("{}", answer);
+            let provider = provider(&args);
+            let warn = |objection: &_| eprintln!("warning: {}", objection);
+            match Submitter::new(&provider).submit(*day, *part, &answer, warn) {
+                Ok(verdict) => println!("{}", verdict),
+                Err(err) => fail(format!("Day {} part {}: {}", day, part, err)),
+            }
+        }
     }
 }
@@ -0,0 +1,143 @@
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::days;
+use crate::solution::Part;
+
+/// The outcome of running one part of one day.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub day: usize,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    #[serde(rename = "elapsed_ms", serialize_with = "as_millis")]
+    pub elapsed: Duration,
+}
+
+impl Report {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+fn as_millis<S: serde::Serializer>(elapsed: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(elapsed.as_secs_f64() * 1000.0)
+}
+
+/// Runs both parts of every registered day against `input_dir/<day>.txt`.
+/// Failures are reported per part rather than stopping the run.
+pub fn run_all(input_dir: &Path) -> Vec<Report> {
+    let mut reports = Vec::new();
+    for day in 1..=days::count() {
+        let solution = days::get(day).expect("registered day");
+        let path = input_dir.join(format!("{}.txt", day));
+        for part in [Part::One, Part::Two] {
+            let start = Instant::now();
+            let result = solution.run(&path, part);
+            let elapsed = start.elapsed();
+            let (answer, error) = match result {
+                Ok(answer) => (Some(answer), None),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            reports.push(Report {
+                day,
+                part: part.into(),
+                answer,
+                error,
+                elapsed,
+            });
+        }
+    }
+    reports
+}
+
+/// Lays the reports out as a plain text table. Multi-line answers, like the
+/// day ten screen, continue on the following lines of the answer column.
+pub fn format_table(reports: &[Report]) -> String {
+    let cells: Vec<Vec<String>> = reports
+        .iter()
+        .map(|report| match (&report.answer, &report.error) {
+            (Some(answer), _) => answer.lines().map(str::to_string).collect(),
+            (None, Some(error)) => vec![format!("error: {}", error)],
+            (None, None) => vec![String::new()],
+        })
+        .collect();
+    let width = cells
+        .iter()
+        .flatten()
+        .map(|line| line.chars().count())
+        .chain(["answer".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut output = format!(
+        "{:>3}  {:>4}  {:<width$}  {:>10}\n",
+        "day", "part", "answer", "time"
+    );
+    for (report, lines) in reports.iter().zip(cells) {
+        for (index, line) in lines.iter().enumerate() {
+            if index == 0 {
+                output.push_str(&format!(
+                    "{:>3}  {:>4}  {:<width$}  {:>10}\n",
+                    report.day,
+                    report.part,
+                    line,
+                    format!("{:.3?}", report.elapsed)
+                ));
+            } else {
+                output.push_str(&format!("{:>3}  {:>4}  {}\n", "", "", line));
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_table() {
+        let reports = vec![
+            Report {
+                day: 1,
+                part: 1,
+                answer: Some("24000".to_string()),
+                error: None,
+                elapsed: Duration::from_millis(2),
+            },
+            Report {
+                day: 10,
+                part: 2,
+                answer: Some("##..\n..##".to_string()),
+                error: None,
+                elapsed: Duration::from_millis(1),
+            },
+            Report {
+                day: 7,
+                part: 1,
+                answer: None,
+                error: Some("line 3: bad".to_string()),
+                elapsed: Duration::ZERO,
+            },
+        ];
+        let table = format_table(&reports);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(5, lines.len());
+        assert!(lines[1].starts_with("  1     1  24000 "));
+        assert_eq!("           ..##", lines[3]);
+        assert!(lines[4].contains("error: line 3: bad"));
+    }
+
+    #[test]
+    fn test_missing_inputs_are_reported() {
+        let reports = run_all(Path::new("/nonexistent"));
+        assert_eq!(days::count() * 2, reports.len());
+        assert!(reports.iter().all(|report| !report.succeeded()));
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    hint::black_box,
+    io::{BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("Unknown part '{}', expected 1 or 2", s)),
+        }
+    }
+}
+
+impl From<Part> for u8 {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", u8::from(*self))
+    }
+}
+
+/// A worked example from the puzzle text, with the answers it gives. Parts
+/// the puzzle does not give an answer for are `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub input: &'static str,
+    pub part_one: Option<&'static str>,
+    pub part_two: Option<&'static str>,
+}
+
+impl Example {
+    pub fn expected(&self, part: Part) -> Option<&'static str> {
+        match part {
+            Part::One => self.part_one,
+            Part::Two => self.part_two,
+        }
+    }
+}
+
+pub trait Solution {
+    type Input;
+    type Answer: Display;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Self::Input>;
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer>;
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer>;
+
+    /// The puzzle's worked examples, the first one is the main example.
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+}
+
+/// Object safe view of a `Solution`, so days with different input and answer
+/// types can live side by side in the registry.
+pub trait Day {
+    fn run_reader(&self, input: &mut dyn BufRead, part: Part) -> Result<String>;
+
+    /// Runs one part once, returning how long parsing and solving each took.
+    fn time(&self, input: &str, part: Part) -> Result<(Duration, Duration)>;
+
+    fn examples(&self) -> &'static [Example];
+
+    fn run(&self, path: &Path, part: Part) -> Result<String> {
+        let mut reader = BufReader::new(File::open(path)?);
+        self.run_reader(&mut reader, part)
+    }
+
+    fn run_str(&self, input: &str, part: Part) -> Result<String> {
+        self.run_reader(&mut input.as_bytes(), part)
+    }
+}
+
+impl<S: Solution> Day for S {
+    fn run_reader(&self, input: &mut dyn BufRead, part: Part) -> Result<String> {
+        let input = self.parse(input)?;
+        let answer = match part {
+            Part::One => self.part_one(&input)?.to_string(),
+            Part::Two => self.part_two(&input)?.to_string(),
+        };
+        Ok(answer)
+    }
+
+    fn time(&self, input: &str, part: Part) -> Result<(Duration, Duration)> {
+        let start = Instant::now();
+        let input = black_box(self.parse(&mut input.as_bytes())?);
+        let parsed = Instant::now();
+        match part {
+            Part::One => black_box(self.part_one(&input)?),
+            Part::Two => black_box(self.part_two(&input)?),
+        };
+        Ok((parsed - start, parsed.elapsed()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        Solution::examples(self)
+    }
+}
+
+/// Parses one of a day's examples, the first one unless an index is given.
+#[cfg(test)]
+macro_rules! example_input {
+    ($day:expr) => {
+        $crate::solution::example_input!($day, 0)
+    };
+    ($day:expr, $index:expr) => {{
+        use $crate::solution::Solution;
+        let example = Solution::examples(&$day)[$index];
+        $day.parse(&mut example.input.as_bytes())
+            .expect("examples parse")
+    }};
+}
+
+/// Adds a test running both parts of every example of a day and checking the
+/// answers the puzzle gives.
+#[cfg(test)]
+macro_rules! test_examples {
+    ($day:expr) => {
+        #[test]
+        fn test_examples() {
+            use $crate::solution::{Day, Part, Solution};
+            let examples = Solution::examples(&$day);
+            assert!(!examples.is_empty(), "no examples");
+            for (index, example) in examples.iter().enumerate() {
+                for part in [Part::One, Part::Two] {
+                    if let Some(expected) = example.expected(part) {
+                        let actual = $day.run_str(example.input, part).unwrap_or_else(|err| {
+                            panic!("example {} part {}: {}", index + 1, part, err)
+                        });
+                        assert_eq!(expected, actual, "example {} part {}", index + 1, part);
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+pub(crate) use {example_input, test_examples};
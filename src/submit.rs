@@ -0,0 +1,341 @@
+use std::{fmt::Display, fs, path::PathBuf};
+
+use reqwest::header;
+
+use crate::error::{Error, Result};
+use crate::input::{client, write_atomically, InputProvider, YEAR};
+use crate::solution::Part;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// The server refused to judge the answer, with how long to wait if it said.
+    RateLimited(Option<String>),
+}
+
+impl Verdict {
+    /// Reads the verdict out of the HTML page returned after posting an answer.
+    pub fn from_response(body: &str) -> Option<Verdict> {
+        if body.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if body.contains("You gave an answer too recently") {
+            let wait = body
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Some(Verdict::RateLimited(wait))
+        } else if body.contains("That's not the right answer") {
+            if body.contains("your answer is too high") {
+                Some(Verdict::TooHigh)
+            } else if body.contains("your answer is too low") {
+                Some(Verdict::TooLow)
+            } else {
+                Some(Verdict::Wrong)
+            }
+        } else {
+            None
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::RateLimited(_) => "rate-limited",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Verdict> {
+        match key {
+            "correct" => Some(Verdict::Correct),
+            "wrong" => Some(Verdict::Wrong),
+            "too-high" => Some(Verdict::TooHigh),
+            "too-low" => Some(Verdict::TooLow),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::RateLimited(Some(wait)) => write!(f, "rate limited, wait {}", wait),
+            Verdict::RateLimited(None) => write!(f, "rate limited"),
+        }
+    }
+}
+
+/// Why an answer is suspect, judged from earlier verdicts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Objection {
+    AlreadySolved(String),
+    KnownWrong(Verdict),
+    AboveTooHigh(i64),
+    BelowTooLow(i64),
+}
+
+impl Objection {
+    /// Answers outside a known bound are warned about and submitted anyway,
+    /// one already judged or for a part already solved is never sent.
+    pub fn is_warning(&self) -> bool {
+        matches!(self, Objection::AboveTooHigh(_) | Objection::BelowTooLow(_))
+    }
+}
+
+impl Display for Objection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Objection::AlreadySolved(answer) => write!(f, "already solved with '{}'", answer),
+            Objection::KnownWrong(verdict) => write!(f, "already submitted, it was {}", verdict),
+            Objection::AboveTooHigh(bound) => write!(f, "{} was already too high", bound),
+            Objection::BelowTooLow(bound) => write!(f, "{} was already too low", bound),
+        }
+    }
+}
+
+/// Every judged answer for one day and part, oldest first.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VerdictLog {
+    entries: Vec<(String, Verdict)>,
+}
+
+impl VerdictLog {
+    pub fn parse(contents: &str) -> VerdictLog {
+        let entries = contents
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter_map(|(key, answer)| Verdict::from_key(key).map(|v| (answer.to_string(), v)))
+            .collect();
+        VerdictLog { entries }
+    }
+
+    pub fn record(&mut self, answer: &str, verdict: Verdict) {
+        if !matches!(verdict, Verdict::RateLimited(_)) {
+            self.entries.push((answer.to_string(), verdict));
+        }
+    }
+
+    pub fn objection(&self, answer: &str) -> Option<Objection> {
+        // An answer judged before says exactly what became of it, even once
+        // the part is solved.
+        match self.entries.iter().find(|(a, _)| a == answer) {
+            Some((_, Verdict::Correct)) => {
+                return Some(Objection::AlreadySolved(answer.to_string()))
+            }
+            Some((_, verdict)) => return Some(Objection::KnownWrong(verdict.clone())),
+            None => {}
+        }
+        if let Some((correct, _)) = self.entries.iter().find(|(_, v)| *v == Verdict::Correct) {
+            return Some(Objection::AlreadySolved(correct.clone()));
+        }
+        let value = answer.trim().parse::<i64>().ok()?;
+        let bound = |wanted: Verdict| {
+            self.entries
+                .iter()
+                .filter(move |(_, v)| *v == wanted)
+                .filter_map(|(a, _)| a.trim().parse::<i64>().ok())
+        };
+        if let Some(high) = bound(Verdict::TooHigh).min().filter(|high| value >= *high) {
+            return Some(Objection::AboveTooHigh(high));
+        }
+        if let Some(low) = bound(Verdict::TooLow).max().filter(|low| value <= *low) {
+            return Some(Objection::BelowTooLow(low));
+        }
+        None
+    }
+}
+
+impl Display for VerdictLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (answer, verdict) in &self.entries {
+            writeln!(f, "{}\t{}", verdict.key(), answer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Posts answers and remembers the verdicts next to the cached inputs.
+pub struct Submitter<'a> {
+    provider: &'a InputProvider,
+}
+
+impl<'a> Submitter<'a> {
+    pub fn new(provider: &'a InputProvider) -> Submitter<'a> {
+        Submitter { provider }
+    }
+
+    fn log_path(&self, day: usize, part: Part) -> PathBuf {
+        self.provider
+            .year_dir()
+            .join("verdicts")
+            .join(format!("{}-{}.tsv", day, part))
+    }
+
+    pub fn log(&self, day: usize, part: Part) -> Result<VerdictLog> {
+        match fs::read_to_string(self.log_path(day, part)) {
+            Ok(contents) => Ok(VerdictLog::parse(&contents)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(VerdictLog::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Submits `answer` unless it was already judged or the part is already
+    /// solved. Being outside a known too high/too low bound is only handed to
+    /// `warn` before the answer is sent.
+    pub fn submit(
+        &self,
+        day: usize,
+        part: Part,
+        answer: &str,
+        warn: impl FnOnce(&Objection),
+    ) -> Result<Verdict> {
+        let mut log = self.log(day, part)?;
+        match log.objection(answer) {
+            Some(objection) if objection.is_warning() => warn(&objection),
+            Some(objection) => {
+                return Err(Error::semantic(format!(
+                    "refusing to submit '{}': {}",
+                    answer, objection
+                )))
+            }
+            None => {}
+        }
+        let verdict = self.post(day, part, answer)?;
+        log.record(answer, verdict.clone());
+        write_atomically(&self.log_path(day, part), &log.to_string())?;
+        Ok(verdict)
+    }
+
+    fn post(&self, day: usize, part: Part, answer: &str) -> Result<Verdict> {
+        let url = format!("{}/{}/day/{}/answer", self.provider.base_url(), YEAR, day);
+        let response = client()?
+            .post(&url)
+            .header(
+                header::COOKIE,
+                format!("session={}", self.provider.session()?),
+            )
+            .form(&[("level", part.to_string()), ("answer", answer.to_string())])
+            .send()
+            .map_err(|err| Error::Fetch(format!("{}: {}", url, err)))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::Fetch(format!("{} returned {}", url, status)));
+        }
+        let body = response
+            .text()
+            .map_err(|err| Error::Fetch(format!("{}: {}", url, err)))?;
+        Verdict::from_response(&body)
+            .ok_or_else(|| Error::Fetch(format!("{} returned an unrecognised page", url)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::tests::{serve, temp_dir};
+
+    #[test]
+    fn test_verdict_from_response() {
+        assert_eq!(
+            Some(Verdict::Correct),
+            Verdict::from_response("<p>That's the right answer! You are one gold star closer.</p>")
+        );
+        assert_eq!(
+            Some(Verdict::TooHigh),
+            Verdict::from_response("<p>That's not the right answer; your answer is too high.</p>")
+        );
+        assert_eq!(
+            Some(Verdict::TooLow),
+            Verdict::from_response("<p>That's not the right answer; your answer is too low.</p>")
+        );
+        assert_eq!(
+            Some(Verdict::Wrong),
+            Verdict::from_response("<p>That's not the right answer. If you're stuck</p>")
+        );
+        assert_eq!(
+            Some(Verdict::RateLimited(Some("34s".to_string()))),
+            Verdict::from_response(
+                "<p>You gave an answer too recently. You have 34s left to wait.</p>"
+            )
+        );
+        assert_eq!(None, Verdict::from_response("<p>Something else</p>"));
+    }
+
+    #[test]
+    fn test_objections() {
+        let mut log = VerdictLog::default();
+        log.record("100", Verdict::TooHigh);
+        log.record("10", Verdict::TooLow);
+        log.record("50", Verdict::Wrong);
+        log.record("60", Verdict::RateLimited(None));
+        assert_eq!(
+            Some(Objection::KnownWrong(Verdict::Wrong)),
+            log.objection("50")
+        );
+        assert_eq!(Some(Objection::AboveTooHigh(100)), log.objection("120"));
+        assert_eq!(Some(Objection::BelowTooLow(10)), log.objection("3"));
+        assert_eq!(None, log.objection("60"));
+        assert_eq!(log, VerdictLog::parse(&log.to_string()));
+
+        log.record("42", Verdict::Correct);
+        assert_eq!(
+            Some(Objection::AlreadySolved("42".to_string())),
+            log.objection("42")
+        );
+        assert_eq!(
+            Some(Objection::AlreadySolved("42".to_string())),
+            log.objection("43")
+        );
+        // Solving the part does not turn a known-wrong answer into a warning.
+        let objection = log.objection("50").unwrap();
+        assert_eq!(Objection::KnownWrong(Verdict::Wrong), objection);
+        assert!(!objection.is_warning());
+        assert!(!log.objection("43").unwrap().is_warning());
+    }
+
+    #[test]
+    fn test_submit_records_verdict() {
+        let (base_url, requests) = serve(vec![
+            (
+                200,
+                "<p>That's not the right answer; your answer is too low.</p>",
+            ),
+            (200, "<p>That's not the right answer. If you're stuck</p>"),
+        ]);
+        let provider = InputProvider::new(&base_url, temp_dir("submit"), Some("abc".to_string()));
+        let submitter = Submitter::new(&provider);
+        let no_warning = |objection: &Objection| panic!("unexpected warning: {}", objection);
+
+        assert_eq!(
+            Verdict::TooLow,
+            submitter.submit(4, Part::Two, "12", no_warning).unwrap()
+        );
+        let request = requests.recv().unwrap();
+        assert_eq!("POST /2022/day/4/answer HTTP/1.1", request.request_line);
+        assert_eq!("level=2&answer=12", request.body);
+
+        // A known-wrong answer never reaches the server.
+        assert!(submitter.submit(4, Part::Two, "12", no_warning).is_err());
+
+        // One below the known bound is warned about and still sent.
+        let mut warnings = Vec::new();
+        let verdict = submitter
+            .submit(4, Part::Two, "7", |objection| {
+                warnings.push(objection.clone())
+            })
+            .unwrap();
+        assert_eq!(Verdict::Wrong, verdict);
+        assert_eq!(vec![Objection::BelowTooLow(12)], warnings);
+        assert_eq!("level=2&answer=7", requests.recv().unwrap().body);
+        fs::remove_dir_all(provider.year_dir().parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,217 @@
+use std::{collections::BTreeMap, fmt::Display, fs, path::Path};
+
+use crate::error::{Error, Result};
+use crate::runner::Report;
+
+/// Known good answers, stored as TOML with one table per day keyed by part:
+///
+/// ```toml
+/// [1]
+/// 1 = "24000"
+/// 2 = "45000"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    days: BTreeMap<usize, BTreeMap<u8, String>>,
+}
+
+impl Answers {
+    pub fn parse(contents: &str) -> Result<Answers> {
+        let raw: BTreeMap<String, BTreeMap<String, String>> =
+            toml::from_str(contents).map_err(|err| Error::semantic(format!("answers: {}", err)))?;
+        let mut answers = Answers::default();
+        for (day, parts) in raw {
+            let day = day
+                .parse::<usize>()
+                .map_err(|_| Error::semantic(format!("answers: '{}' is not a day", day)))?;
+            for (part, answer) in parts {
+                let part = part
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|part| *part == 1 || *part == 2)
+                    .ok_or_else(|| {
+                        Error::semantic(format!("answers: day {} has no part '{}'", day, part))
+                    })?;
+                answers.insert(day, part, &answer);
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn load(path: &Path) -> Result<Answers> {
+        Answers::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_toml())?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: usize, part: u8) -> Option<&str> {
+        self.days.get(&day)?.get(&part).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: usize, part: u8, answer: &str) {
+        self.days
+            .entry(day)
+            .or_default()
+            .insert(part, answer.to_string());
+    }
+
+    pub fn to_toml(&self) -> String {
+        let raw: BTreeMap<String, BTreeMap<String, &String>> = self
+            .days
+            .iter()
+            .map(|(day, parts)| {
+                let parts = parts.iter().map(|(p, a)| (p.to_string(), a)).collect();
+                (day.to_string(), parts)
+            })
+            .collect();
+        toml::to_string(&raw).expect("answers are always representable as TOML")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Match,
+    Mismatch {
+        expected: String,
+    },
+    Failed(String),
+    /// No answer is stored for this day and part yet.
+    Unchecked,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: usize,
+    pub part: u8,
+    pub actual: Option<String>,
+    pub status: Status,
+}
+
+impl Check {
+    /// Only an answer matching the stored one passes, one with nothing
+    /// stored to compare against does not.
+    pub fn passed(&self) -> bool {
+        self.status == Status::Match
+    }
+
+    pub fn is_unchecked(&self) -> bool {
+        self.status == Status::Unchecked
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day {} part {}: ", self.day, self.part)?;
+        match &self.status {
+            Status::Match => write!(f, "ok"),
+            Status::Unchecked => write!(f, "no stored answer"),
+            Status::Failed(error) => write!(f, "FAILED, {}", error),
+            Status::Mismatch { expected } => write!(
+                f,
+                "MISMATCH, expected {:?} got {:?}",
+                expected,
+                self.actual.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Compares freshly computed reports against the stored answers.
+pub fn check(reports: &[Report], answers: &Answers) -> Vec<Check> {
+    reports
+        .iter()
+        .map(|report| {
+            let status = match (&report.answer, &report.error) {
+                (_, Some(error)) => Status::Failed(error.clone()),
+                (Some(actual), None) => match answers.get(report.day, report.part) {
+                    None => Status::Unchecked,
+                    Some(expected) if expected == actual => Status::Match,
+                    Some(expected) => Status::Mismatch {
+                        expected: expected.to_string(),
+                    },
+                },
+                (None, None) => Status::Failed("no answer".to_string()),
+            };
+            Check {
+                day: report.day,
+                part: report.part,
+                actual: report.answer.clone(),
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Stores every successfully computed answer, keeping the ones already known
+/// for days that failed.
+pub fn record(reports: &[Report], answers: &mut Answers) {
+    for report in reports {
+        if let Some(answer) = &report.answer {
+            answers.insert(report.day, report.part, answer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn report(day: usize, part: u8, answer: Result<&str, &str>) -> Report {
+        Report {
+            day,
+            part,
+            answer: answer.ok().map(str::to_string),
+            error: answer.err().map(str::to_string),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_answers_round_trip() {
+        let answers =
+            Answers::parse("[1]\n1 = \"24000\"\n2 = \"45000\"\n\n[10]\n2 = \"#.\\n.#\"\n").unwrap();
+        assert_eq!(Some("45000"), answers.get(1, 2));
+        assert_eq!(Some("#.\n.#"), answers.get(10, 2));
+        assert_eq!(None, answers.get(10, 1));
+        assert_eq!(answers, Answers::parse(&answers.to_toml()).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_answers() {
+        assert!(Answers::parse("[1]\n3 = \"1\"\n").is_err());
+        assert!(Answers::parse("[one]\n1 = \"1\"\n").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers =
+            Answers::parse("[1]\n1 = \"24000\"\n2 = \"45000\"\n[2]\n1 = \"15\"\n").unwrap();
+        let reports = vec![
+            report(1, 1, Ok("24000")),
+            report(1, 2, Ok("41000")),
+            report(2, 1, Err("line 1: bad")),
+            report(2, 2, Ok("12")),
+        ];
+        let checks = check(&reports, &answers);
+        assert_eq!(Status::Match, checks[0].status);
+        assert_eq!(
+            Status::Mismatch {
+                expected: "45000".to_string()
+            },
+            checks[1].status
+        );
+        assert!(!checks[2].passed());
+        assert_eq!(Status::Unchecked, checks[3].status);
+        assert!(!checks[3].passed());
+        assert!(checks[3].is_unchecked());
+        // Nothing stored at all verifies nothing.
+        assert!(check(&reports[..1], &Answers::default())
+            .iter()
+            .all(|check| !check.passed() && check.is_unchecked()));
+    }
+}
@@ -0,0 +1,68 @@
+//! Checks how `run` picks its input when no path is given, by running the
+//! binary with different kinds of stdin.
+
+use std::{
+    env, fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+/// A cache directory holding day one's example as the cached input.
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("advent-2022-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("2022")).unwrap();
+    fs::write(
+        dir.join("2022").join("1.txt"),
+        include_str!("../fixtures/1.txt"),
+    )
+    .unwrap();
+    dir
+}
+
+fn run_day_one(cache_dir: &PathBuf, stdin: Stdio, piped: Option<&str>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_advent-2022"))
+        .args(["run", "1", "--part", "1", "--cache-dir"])
+        .arg(cache_dir)
+        // Nothing is fetched, an unreachable server makes sure of it.
+        .args(["--base-url", "http://127.0.0.1:9"])
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    if let Some(input) = piped {
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn empty_stdin_falls_back_to_cache() {
+    let dir = cache_dir("stdin-null");
+    let output = run_day_one(&dir, Stdio::null(), None);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!("24000\n", String::from_utf8_lossy(&output.stdout));
+
+    let output = run_day_one(&dir, Stdio::piped(), Some(""));
+    assert_eq!("24000\n", String::from_utf8_lossy(&output.stdout));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn piped_stdin_is_read() {
+    let dir = cache_dir("stdin-piped");
+    let output = run_day_one(&dir, Stdio::piped(), Some("1\n\n5\n"));
+    assert_eq!("5\n", String::from_utf8_lossy(&output.stdout));
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,30 @@
+//! Runs every day against real puzzle inputs and checks the answers stored
+//! alongside them, so refactors cannot silently change a result.
+//!
+//! Inputs are personal and not committed, so the test is ignored by default.
+//! Point `ADVENT_INPUT_DIR` at a directory holding `<day>.txt` inputs and an
+//! `answers.toml` (see `advent-2022 verify --record`) and run it with:
+//!
+//! ```sh
+//! ADVENT_INPUT_DIR=~/.cache/advent-2022/2022 cargo test -- --ignored
+//! ```
+
+use std::{env, path::PathBuf};
+
+use advent_2022::{runner, verify, verify::Answers};
+
+#[test]
+#[ignore = "needs personal inputs in ADVENT_INPUT_DIR"]
+fn stored_answers_still_match() {
+    let input_dir = PathBuf::from(
+        env::var_os("ADVENT_INPUT_DIR").expect("ADVENT_INPUT_DIR should name the input directory"),
+    );
+    let answers = Answers::load(&input_dir.join("answers.toml")).expect("readable answers.toml");
+    let reports = runner::run_all(&input_dir);
+    let failures: Vec<String> = verify::check(&reports, &answers)
+        .iter()
+        .filter(|check| !check.passed())
+        .map(ToString::to_string)
+        .collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}